@@ -0,0 +1,99 @@
+//! Host benchmark of the pulse encoding, comparing the nibble lookup table with the previous bit-by-bit encoder.
+//!
+//! Run with `cargo run --release` in this directory.
+//! The encoder doesn’t depend on esp-hal, so this uses the actual encoder source with plain `u32`s as pulses.
+//! Absolute numbers on the host are much lower than on the microcontrollers, but the relative speedup carries over.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[path = "../../../src/encoding.rs"]
+#[allow(dead_code)]
+mod encoding;
+
+use encoding::PulseLut;
+
+const ZERO: u32 = 0x8040_0020;
+const ONE: u32 = 0x8020_0040;
+const LEDS: usize = 1000;
+const CHANNELS: usize = 3;
+
+/// The previous encoder, which loops over every bit of every channel.
+fn encode_bitwise<'a>(
+    channel_value: usize,
+    bits: usize,
+    mut_iter: &mut impl Iterator<Item = &'a mut u32>,
+) -> Result<(), ()> {
+    for index in (0..bits).rev() {
+        let position = 1 << index;
+        *mut_iter.next().ok_or(())? = match channel_value & position {
+            0 => ZERO,
+            _ => ONE,
+        }
+    }
+    Ok(())
+}
+
+fn frame_bitwise<const BITS: usize>(colors: &[[usize; CHANNELS]], buffer: &mut [u32]) {
+    let mut iter = buffer.iter_mut();
+    for color in colors {
+        for &channel in color {
+            encode_bitwise(channel, BITS, &mut iter).unwrap();
+        }
+    }
+}
+
+fn frame_lut<const BITS: usize>(
+    colors: &[[usize; CHANNELS]],
+    buffer: &mut [u32],
+    lut: &PulseLut<u32>,
+) {
+    for (color, out) in colors.iter().zip(buffer.chunks_exact_mut(CHANNELS * BITS)) {
+        for (&channel, out) in color.iter().zip(out.as_chunks_mut::<BITS>().0) {
+            lut.encode(channel, out);
+        }
+    }
+}
+
+/// Returns the average time of one call, over at least half a second.
+fn measure(mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    let mut iterations = 0;
+    while start.elapsed() < Duration::from_millis(500) {
+        f();
+        iterations += 1;
+    }
+    start.elapsed() / iterations
+}
+
+fn compare<const BITS: usize>(name: &str) {
+    // A simple pseudo-random pattern, so that the branches of the bitwise encoder are unpredictable.
+    let mut state = 0x1234_5678u32;
+    let colors: Vec<[usize; CHANNELS]> = (0..LEDS)
+        .map(|_| {
+            core::array::from_fn(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as usize & ((1 << BITS) - 1)
+            })
+        })
+        .collect();
+    let mut buffer = vec![0u32; LEDS * CHANNELS * BITS];
+    let lut = PulseLut::new(ZERO, ONE);
+
+    let bitwise = measure(|| frame_bitwise::<BITS>(black_box(&colors), black_box(&mut buffer)));
+    let expected = buffer.clone();
+    let table = measure(|| frame_lut::<BITS>(black_box(&colors), black_box(&mut buffer), &lut));
+    assert_eq!(buffer, expected, "encoders disagree");
+
+    println!(
+        "{name}: {LEDS} LEDs, bitwise {bitwise:?} per frame, lookup table {table:?} per frame, {:.1}× faster",
+        bitwise.as_secs_f64() / table.as_secs_f64()
+    );
+}
+
+fn main() {
+    compare::<8>("RGB8");
+    compare::<16>("RGB16");
+}
@@ -5,8 +5,8 @@
 //!
 //! The demo will leverage the [`smart_leds`](https://crates.io/crates/smart-leds)
 //! crate functionality to circle through the HSV hue color space (with
-//! saturation and value both at 255). Additionally, we apply a gamma correction
-//! and limit the brightness to 10 (out of 255).
+//! saturation and value both at 255). Additionally, the driver applies a gamma
+//! correction and limits the brightness to 10 (out of 255).
 //!
 //! The following wiring is assumed for ESP32:
 //! - LED => GPIO33
@@ -30,14 +30,17 @@
 use core::cfg_select;
 use esp_backtrace as _;
 use esp_hal::{delay::Delay, rmt::Rmt, time::Rate};
-use esp_hal_smartled::{RmtSmartLeds, Ws2812Timing, buffer_size, color_order};
+use esp_hal_smartled::{RmtSmartLeds, Ws2812Timing, buffer_size, color_order, correction::GammaLut};
 use smart_leds::{
-    RGB8, SmartLedsWrite, brightness, gamma,
+    RGB8, SmartLedsWrite,
     hsv::{Hsv, hsv2rgb},
 };
 
 esp_bootloader_esp_idf::esp_app_desc!();
 
+// Same gamma exponent as `smart_leds::gamma`, computed at compile time.
+static GAMMA: GammaLut = GammaLut::gamma(2.8);
+
 #[esp_hal::main]
 fn main() -> ! {
     let peripherals = esp_hal::init(esp_hal::Config::default());
@@ -81,6 +84,11 @@ fn main() -> ! {
             2,
         ).unwrap()
     };
+    // When sending to the LED, the driver does a gamma correction first (see the
+    // `correction` module documentation for details) and then limits the brightness
+    // to 10 out of 255 so that the output is not too bright.
+    led.set_gamma(Some(&GAMMA));
+    led.set_brightness(10);
     let delay = Delay::new();
 
     let mut color = Hsv {
@@ -97,11 +105,7 @@ fn main() -> ! {
             // Convert from the HSV color space (where we can easily transition from one
             // color to the other) to the RGB color space that we can then send to the LED
             data = [hsv2rgb(color)];
-            // When sending to the LED, we do a gamma correction first (see smart_leds
-            // documentation for details) and then limit the brightness to 10 out of 255 so
-            // that the output it's not too bright.
-            led.write(brightness(gamma(data.iter().cloned()), 10))
-                .unwrap();
+            led.write(data.iter().cloned()).unwrap();
             delay.delay_millis(20);
         }
     }
@@ -21,15 +21,18 @@ use esp_backtrace as _;
 use esp_hal::interrupt::software::SoftwareInterruptControl;
 use esp_hal::timer::timg::TimerGroup;
 use esp_hal::{rmt::Rmt, time::Rate};
-use esp_hal_smartled::{RmtSmartLeds, Ws2811LowSpeedTiming, Ws2811Timing, Ws2812Timing, Ws2812bTiming, buffer_size, color_order};
+use esp_hal_smartled::{RmtSmartLeds, Ws2811LowSpeedTiming, Ws2811Timing, Ws2812Timing, Ws2812bTiming, buffer_size, color_order, correction::GammaLut};
 use smart_leds::RGB8;
 use smart_leds::{
-    SmartLedsWriteAsync, brightness, gamma,
+    SmartLedsWriteAsync,
     hsv::{Hsv, hsv2rgb},
 };
 
 esp_bootloader_esp_idf::esp_app_desc!();
 
+// Same gamma exponent as `smart_leds::gamma`, computed at compile time.
+static GAMMA: GammaLut = GammaLut::gamma(2.8);
+
 #[esp_rtos::main]
 async fn main(spawner: Spawner) -> ! {
     let peripherals = esp_hal::init(esp_hal::Config::default());
@@ -80,6 +83,11 @@ async fn main(spawner: Spawner) -> ! {
         )
         .unwrap()
     };
+    // When sending to the LED, the driver does a gamma correction first (see the
+    // `correction` module documentation for details) and then limits the brightness
+    // to 10 out of 255 so that the output is not too bright.
+    led.set_gamma(Some(&GAMMA));
+    led.set_brightness(10);
 
     let mut color = Hsv {
         hue: 0,
@@ -97,12 +105,9 @@ async fn main(spawner: Spawner) -> ! {
             // Convert from the HSV color space (where we can easily transition from one
             // color to the other) to the RGB color space that we can then send to the LED
             data = [hsv2rgb(color); LEDS];
-            // When sending to the LED, we do a gamma correction first (see smart_leds
-            // documentation for details) and then limit the brightness to 10 out of 255 so
-            // that the output it's not too bright.
 
             // This call already prepares the buffer.
-            let fut = led.write(brightness(gamma(data.iter().cloned()), 10));
+            let fut = led.write(data.iter().cloned());
             // Put more led.write() calls (for other drivers) and other peripheral preparations here...
 
             // Dispatch all the LED writes at once.
@@ -0,0 +1,167 @@
+//! Access to the channels of all [`smart_leds_trait`] color types.
+
+use num_traits::{FromPrimitive, Unsigned, Zero};
+use smart_leds_trait::{CctWhite, RGB, RGBCCT, RGBW, White};
+
+/// Utility trait that retrieves metadata about all [`smart_leds_trait`] color types.
+pub trait Color {
+    /// The maximum channel number this color supports.
+    ///
+    /// - For RGB (or any permutation thereof), this is 3.
+    /// - For RGBW, this is 4.
+    /// - For RGBCCT, this is 5.
+    /// - For CCT, this is 2.
+    ///
+    /// Note that this channel count is used by users of [`ColorOrder`](crate::ColorOrder) to limit the channel number that’s passed into [`ColorOrder::get_channel_data`](crate::ColorOrder::get_channel_data).
+    const CHANNELS: u8;
+
+    /// Type of a single channel of this color. Usually [`u8`], but [`u16`] is also used for some LEDs.
+    type ChannelType: Unsigned + Copy + Into<usize> + FromPrimitive;
+
+    /// Retrieve the value of the provided channel, in the natural order of the color type.
+    ///
+    /// This order is red, green, blue, followed by the white channel(s) if present; for RGBCCT and CCT, cool white comes before warm white.
+    /// It is independent of the [`ColorOrder`](crate::ColorOrder) of the LEDs, and is the order used by color corrections.
+    ///
+    /// The maximum channel number users are allowed to pass in is [`Color::CHANNELS`] minus one.
+    /// If this restriction is not upheld, the implementation may panic.
+    ///
+    /// The default implementation returns zero for every channel, so that existing implementations of this trait keep working.
+    /// Together with the default [`Color::set_channel`], this means that color corrections leave such colors unchanged, and that power limiting counts them as switched off.
+    /// Implement both methods to make these features work with a custom color type.
+    fn channel(&self, channel: u8) -> Self::ChannelType {
+        let _ = channel;
+        Self::ChannelType::zero()
+    }
+
+    /// Set the value of the provided channel, in the same order as [`Color::channel`].
+    ///
+    /// The default implementation ignores the value; see [`Color::channel`].
+    fn set_channel(&mut self, channel: u8, value: Self::ChannelType) {
+        let _ = (channel, value);
+    }
+}
+
+impl<T> Color for RGB<T>
+where
+    T: Unsigned + Copy + Into<usize> + FromPrimitive,
+{
+    const CHANNELS: u8 = 3;
+    type ChannelType = T;
+
+    fn channel(&self, channel: u8) -> T {
+        match channel {
+            0 => self.r,
+            1 => self.g,
+            2 => self.b,
+            _ => unreachable!(),
+        }
+    }
+
+    fn set_channel(&mut self, channel: u8, value: T) {
+        match channel {
+            0 => self.r = value,
+            1 => self.g = value,
+            2 => self.b = value,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<T> Color for RGBW<T>
+where
+    T: Unsigned + Copy + Into<usize> + FromPrimitive,
+{
+    const CHANNELS: u8 = 4;
+    type ChannelType = T;
+
+    fn channel(&self, channel: u8) -> T {
+        match channel {
+            0 => self.r,
+            1 => self.g,
+            2 => self.b,
+            3 => self.a.0,
+            _ => unreachable!(),
+        }
+    }
+
+    fn set_channel(&mut self, channel: u8, value: T) {
+        match channel {
+            0 => self.r = value,
+            1 => self.g = value,
+            2 => self.b = value,
+            3 => self.a.0 = value,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<T> Color for RGBCCT<T>
+where
+    T: Unsigned + Copy + Into<usize> + FromPrimitive,
+{
+    const CHANNELS: u8 = 5;
+    type ChannelType = T;
+
+    fn channel(&self, channel: u8) -> T {
+        match channel {
+            0 => self.r,
+            1 => self.g,
+            2 => self.b,
+            3 => self.a.cold,
+            4 => self.a.warm,
+            _ => unreachable!(),
+        }
+    }
+
+    fn set_channel(&mut self, channel: u8, value: T) {
+        match channel {
+            0 => self.r = value,
+            1 => self.g = value,
+            2 => self.b = value,
+            3 => self.a.cold = value,
+            4 => self.a.warm = value,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<T> Color for White<T>
+where
+    T: Unsigned + Copy + Into<usize> + FromPrimitive,
+{
+    const CHANNELS: u8 = 1;
+    type ChannelType = T;
+
+    fn channel(&self, _channel: u8) -> T {
+        self.0
+    }
+
+    fn set_channel(&mut self, _channel: u8, value: T) {
+        self.0 = value;
+    }
+}
+
+impl<T> Color for CctWhite<T>
+where
+    T: Unsigned + Copy + Into<usize> + FromPrimitive,
+{
+    const CHANNELS: u8 = 2;
+    type ChannelType = T;
+
+    fn channel(&self, channel: u8) -> T {
+        match channel {
+            0 => self.cold,
+            1 => self.warm,
+            _ => unreachable!(),
+        }
+    }
+
+    fn set_channel(&mut self, channel: u8, value: T) {
+        match channel {
+            0 => self.cold = value,
+            1 => self.warm = value,
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Color temperature helpers for white light.
+//!
+//! Tunable-white strips have a warm white and a cool white LED per pixel ([`CctWhite`], or the white part of [`RGBCCT`](smart_leds_trait::RGBCCT)).
+//! [`TunableWhite`] mixes those two channels to produce any color temperature between the LEDs’ own temperatures:
+//!
+//! ```rust,ignore
+//! // A strip with 2700 K warm white and 6500 K cool white LEDs.
+//! let strip = TunableWhite::new(2700, 6500);
+//! leds.write([strip.color(200, 4000); 60])?;
+//! ```
+//!
+//! For RGB-only strips, [`rgb_white`] approximates a color temperature with the RGB channels.
+
+use smart_leds_trait::{CctWhite, RGB8};
+
+use crate::math;
+
+/// Lowest color temperature supported by the blackbody approximation, in Kelvin.
+const MIN_KELVIN: u16 = 1000;
+/// Highest color temperature supported by the blackbody approximation, in Kelvin.
+const MAX_KELVIN: u16 = 40000;
+
+/// Approximates the color of a blackbody radiator at the given temperature in Kelvin, at full brightness.
+///
+/// This uses Tanner Helland’s curve fit, which is accurate enough for lighting purposes between 1000 K and 40000 K.
+/// Temperatures outside of this range are clamped.
+pub const fn blackbody(kelvin: u16) -> RGB8 {
+    let kelvin = if kelvin < MIN_KELVIN {
+        MIN_KELVIN
+    } else if kelvin > MAX_KELVIN {
+        MAX_KELVIN
+    } else {
+        kelvin
+    };
+    let temperature = kelvin as f64 / 100.;
+
+    let red = if temperature <= 66. {
+        255.
+    } else {
+        329.698727446 * math::pow(temperature - 60., -0.1332047592)
+    };
+    let green = if temperature <= 66. {
+        99.4708025861 * math::ln(temperature) - 161.1195681661
+    } else {
+        288.1221695283 * math::pow(temperature - 60., -0.0755148492)
+    };
+    let blue = if temperature >= 66. {
+        255.
+    } else if temperature <= 19. {
+        0.
+    } else {
+        138.5177312231 * math::ln(temperature - 10.) - 305.0447927307
+    };
+
+    RGB8 {
+        r: math::round_clamped(red, 255) as u8,
+        g: math::round_clamped(green, 255) as u8,
+        b: math::round_clamped(blue, 255) as u8,
+    }
+}
+
+/// Approximates white light of the given color temperature in Kelvin on an RGB LED, scaled to the given brightness.
+///
+/// See [`blackbody`] for the supported temperature range.
+pub const fn rgb_white(brightness: u8, kelvin: u16) -> RGB8 {
+    let color = blackbody(kelvin);
+    RGB8 {
+        r: scale(color.r, brightness),
+        g: scale(color.g, brightness),
+        b: scale(color.b, brightness),
+    }
+}
+
+/// Mixes the warm and cool white channels of a tunable-white LED to produce a given color temperature.
+///
+/// The mix is interpolated in mired (reciprocal color temperature) space, where perceived color differences are approximately uniform.
+/// Temperatures outside of the range of the two LEDs are clamped to the closest LED.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TunableWhite {
+    warm_mired: u32,
+    cool_mired: u32,
+}
+
+impl TunableWhite {
+    /// Describes a strip whose warm white LEDs have `warm_kelvin` and whose cool white LEDs have `cool_kelvin` color temperature.
+    ///
+    /// The temperatures can be found in the LED or strip datasheet; common values are 2700 K and 6500 K.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either temperature is zero or if `warm_kelvin` is not lower than `cool_kelvin`.
+    pub const fn new(warm_kelvin: u16, cool_kelvin: u16) -> Self {
+        assert!(
+            warm_kelvin > 0 && warm_kelvin < cool_kelvin,
+            "warm white temperature must be lower than cool white temperature"
+        );
+        Self {
+            warm_mired: to_mired(warm_kelvin),
+            cool_mired: to_mired(cool_kelvin),
+        }
+    }
+
+    /// Returns the warm and cool white channel values for white light of the given color temperature in Kelvin.
+    ///
+    /// The two channels always add up to `brightness`, so the light output stays constant when only the color temperature changes.
+    pub const fn color(&self, brightness: u8, kelvin: u16) -> CctWhite<u8> {
+        let mired = if kelvin == 0 {
+            self.warm_mired
+        } else {
+            to_mired(kelvin)
+        };
+        let cool_fraction = if mired >= self.warm_mired {
+            0
+        } else if mired <= self.cool_mired {
+            255
+        } else {
+            ((self.warm_mired - mired) * 255 / (self.warm_mired - self.cool_mired)) as u8
+        };
+        let cool = scale(brightness, cool_fraction);
+        CctWhite {
+            cold: cool,
+            warm: brightness - cool,
+        }
+    }
+}
+
+/// Converts a color temperature in Kelvin to mired.
+const fn to_mired(kelvin: u16) -> u32 {
+    1_000_000 / kelvin as u32
+}
+
+/// Scales an 8-bit value by an 8-bit factor, with rounding.
+const fn scale(value: u8, factor: u8) -> u8 {
+    ((value as u16 * factor as u16 + 127) / 255) as u8
+}
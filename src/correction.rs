@@ -0,0 +1,507 @@
+//! Color correction applied by the driver while encoding colors.
+//!
+//! Smart LEDs use PWM to dim, so their light output is linear in the channel value, while human brightness perception is not.
+//! A [`GammaLut`] maps the (perceptual) input values to linear LED output, and the global brightness is applied afterwards in linear space.
+//! This way, a brightness of 50% always halves the emitted light, no matter which transfer function is in use.
+//!
+//! ```rust,ignore
+//! static GAMMA: GammaLut = GammaLut::gamma(2.5);
+//!
+//! led.set_gamma(Some(&GAMMA));
+//! led.set_brightness(10);
+//! led.write(colors)?;
+//! ```
+//!
+//! The lookup tables are computed at compile time when stored in a `const` or `static`.
+//! They have 256 entries at 16-bit precision, so 8-bit channels are looked up directly,
+//! and 16-bit channels are interpolated between entries.
+//!
+//! Additionally, a [`WhiteBalance`] compensates for LED batches whose white is tinted,
+//! a [`CorrectionMatrix`] can mix channels for more complex corrections,
+//! and a per-pixel [`Calibration`] table compensates for variance between individual LEDs.
+//! All corrections are stored in the driver and applied to every subsequent write.
+//!
+//! # Dithering
+//!
+//! At low brightness, only a few output levels remain after gamma correction, so slow fades visibly step.
+//! Since all corrections are computed at 16-bit precision, the driver can instead use temporal dithering:
+//! every write rounds the precise value up or down in a pattern whose average over time matches the precise value.
+//! This requires a refresh loop that writes frames at a steady, high rate (ideally 100 Hz or more), even if the colors don’t change.
+//! Colors with higher precision than the LEDs support (for example `RGB<u16>` for 8-bit LEDs)
+//! can be written with [`RmtSmartLeds::write_high_precision`](crate::RmtSmartLeds::write_high_precision).
+
+use num_traits::FromPrimitive;
+
+use crate::{Color, math};
+
+/// Number of entries in a [`GammaLut`].
+const LUT_SIZE: usize = 256;
+
+/// Lookup table for a transfer function from input channel values to linear LED output.
+#[derive(Clone, PartialEq, Eq)]
+pub struct GammaLut([u16; LUT_SIZE]);
+
+impl GammaLut {
+    /// The identity transfer function, which performs no correction.
+    pub const LINEAR: Self = Self::gamma(1.);
+
+    /// Lightness curve of the CIE 1931 (L\*) color space, the most perceptually uniform of the provided transfer functions.
+    pub const CIE1931: Self = {
+        let mut table = [0; LUT_SIZE];
+        let mut index = 0;
+        while index < LUT_SIZE {
+            let lightness = index as f64 * 100. / (LUT_SIZE - 1) as f64;
+            let luminance = if lightness <= 8. {
+                lightness / 903.3
+            } else {
+                let cube_root = (lightness + 16.) / 116.;
+                cube_root * cube_root * cube_root
+            };
+            table[index] = math::round_clamped(luminance * u16::MAX as f64, u16::MAX as u32) as u16;
+            index += 1;
+        }
+        Self(table)
+    };
+
+    /// Creates a lookup table for a power-law gamma curve with the given exponent.
+    ///
+    /// Exponents between 2.2 and 2.8 are common for LEDs; larger exponents make dark colors darker.
+    ///
+    /// This function is intended to be evaluated at compile time (i.e. in a `const` or `static`), as it is slow at runtime.
+    pub const fn gamma(exponent: f32) -> Self {
+        let mut table = [0; LUT_SIZE];
+        let mut index = 0;
+        while index < LUT_SIZE {
+            let input = index as f64 / (LUT_SIZE - 1) as f64;
+            let output = math::pow(input, exponent as f64);
+            table[index] = math::round_clamped(output * u16::MAX as f64, u16::MAX as u32) as u16;
+            index += 1;
+        }
+        Self(table)
+    }
+
+    /// Creates a lookup table from precomputed values.
+    ///
+    /// Entry `i` is the linear output (`0..=u16::MAX`) for the input `i / 255`.
+    pub const fn from_table(table: [u16; LUT_SIZE]) -> Self {
+        Self(table)
+    }
+
+    /// Looks up the linear output for an input value in `0..=max`, at 16-bit precision.
+    pub(crate) fn lookup(&self, value: u64, max: u64) -> u64 {
+        // 8.8 fixed-point position in the table.
+        let position = value * (LUT_SIZE as u64 - 1) * 256 / max;
+        let index = (position >> 8) as usize;
+        let fraction = position & 0xff;
+        let low = self.0[index] as u64;
+        if fraction == 0 {
+            return low;
+        }
+        let high = self.0[index + 1] as u64;
+        if high >= low {
+            low + (high - low) * fraction / 256
+        } else {
+            low - (low - high) * fraction / 256
+        }
+    }
+}
+
+impl core::fmt::Debug for GammaLut {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("GammaLut").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for GammaLut {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "GammaLut(..)")
+    }
+}
+
+/// Maximum number of channels that color corrections support, which is the channel count of [`RGBCCT`](smart_leds_trait::RGBCCT).
+pub(crate) const MAX_CHANNELS: usize = 5;
+
+/// Per-channel scale factors that compensate for the tint of a batch of LEDs, similar to FastLED’s color correction.
+///
+/// Each channel is scaled by `factor / 255` in linear space. The channels are in the natural order of the color type (see [`Color::channel`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct WhiteBalance([u8; MAX_CHANNELS]);
+
+impl WhiteBalance {
+    /// Performs no white balancing.
+    pub const UNCORRECTED: Self = Self([u8::MAX; MAX_CHANNELS]);
+    /// Typical correction for SMD5050 LED strips, which tend to be blue-green (FastLED’s `TypicalLEDStrip`).
+    pub const TYPICAL_LED_STRIP: Self = Self::rgb(255, 176, 240);
+    /// Typical correction for 8 mm “pixels” and pixel strings (FastLED’s `Typical8mmPixel`).
+    pub const TYPICAL_8MM_PIXEL: Self = Self::rgb(255, 224, 140);
+
+    /// Creates a white balance for RGB colors.
+    pub const fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        Self([red, green, blue, u8::MAX, u8::MAX])
+    }
+
+    /// Creates a white balance for RGBW colors.
+    pub const fn rgbw(red: u8, green: u8, blue: u8, white: u8) -> Self {
+        Self([red, green, blue, white, u8::MAX])
+    }
+
+    /// Creates a white balance from per-channel scale factors. Channels that are not given are not scaled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than five channels are given.
+    pub const fn new(factors: &[u8]) -> Self {
+        assert!(factors.len() <= MAX_CHANNELS, "too many channels");
+        let mut balance = Self::UNCORRECTED;
+        let mut channel = 0;
+        while channel < factors.len() {
+            balance.0[channel] = factors[channel];
+            channel += 1;
+        }
+        balance
+    }
+
+    /// Returns the scale factor of a channel.
+    pub const fn factor(&self, channel: u8) -> u8 {
+        self.0[channel as usize]
+    }
+}
+
+impl Default for WhiteBalance {
+    fn default() -> Self {
+        Self::UNCORRECTED
+    }
+}
+
+/// A fixed-point color correction matrix, which mixes the channels of each color.
+///
+/// Output channel `i` is the sum of all input channels `j` multiplied with the entry in row `i` and column `j`,
+/// computed in linear space and clamped to the valid range.
+/// Entries are fixed-point numbers where [`CorrectionMatrix::ONE`] represents 1.0, so the range is roughly -8.0 to 8.0.
+///
+/// The channels are in the natural order of the color type (see [`Color::channel`]).
+/// Channels beyond the size of the given matrix are passed through unchanged, so an RGB matrix leaves the white channel of RGBW colors alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CorrectionMatrix([[i16; MAX_CHANNELS]; MAX_CHANNELS]);
+
+impl CorrectionMatrix {
+    /// The fixed-point representation of 1.0.
+    pub const ONE: i16 = 1 << Self::FRACTION_BITS;
+    const FRACTION_BITS: u32 = 12;
+
+    /// The identity matrix, which performs no correction.
+    pub const IDENTITY: Self = {
+        let mut matrix = [[0; MAX_CHANNELS]; MAX_CHANNELS];
+        let mut channel = 0;
+        while channel < MAX_CHANNELS {
+            matrix[channel][channel] = Self::ONE;
+            channel += 1;
+        }
+        Self(matrix)
+    };
+
+    /// Creates a correction matrix from fixed-point entries, where [`CorrectionMatrix::ONE`] represents 1.0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is larger than five.
+    pub const fn from_fixed<const N: usize>(entries: [[i16; N]; N]) -> Self {
+        assert!(N <= MAX_CHANNELS, "too many channels");
+        let mut matrix = Self::IDENTITY;
+        let mut row = 0;
+        while row < N {
+            let mut column = 0;
+            while column < N {
+                matrix.0[row][column] = entries[row][column];
+                column += 1;
+            }
+            row += 1;
+        }
+        matrix
+    }
+
+    /// Creates a 3x3 correction matrix for RGB colors.
+    pub const fn rgb(entries: [[f32; 3]; 3]) -> Self {
+        Self::from_fixed(Self::to_fixed(entries))
+    }
+
+    /// Creates a 4x4 correction matrix for RGBW colors.
+    pub const fn rgbw(entries: [[f32; 4]; 4]) -> Self {
+        Self::from_fixed(Self::to_fixed(entries))
+    }
+
+    const fn to_fixed<const N: usize>(entries: [[f32; N]; N]) -> [[i16; N]; N] {
+        let mut fixed = [[0; N]; N];
+        let mut row = 0;
+        while row < N {
+            let mut column = 0;
+            while column < N {
+                let scaled = entries[row][column] * Self::ONE as f32;
+                let rounded = if scaled < 0. {
+                    scaled - 0.5
+                } else {
+                    scaled + 0.5
+                };
+                fixed[row][column] = if rounded >= i16::MAX as f32 {
+                    i16::MAX
+                } else if rounded <= i16::MIN as f32 {
+                    i16::MIN
+                } else {
+                    rounded as i16
+                };
+                column += 1;
+            }
+            row += 1;
+        }
+        fixed
+    }
+
+    fn apply(&self, values: &mut [u32; MAX_CHANNELS], channels: usize) {
+        let input = *values;
+        for (output, row) in values.iter_mut().zip(&self.0).take(channels) {
+            let sum: i64 = row
+                .iter()
+                .zip(&input)
+                .take(channels)
+                .map(|(&factor, &value)| factor as i64 * value as i64)
+                .sum();
+            *output = (sum >> Self::FRACTION_BITS).clamp(0, u16::MAX as i64) as u32;
+        }
+    }
+}
+
+/// Per-pixel calibration table with per-channel gains for every LED, to compensate for manufacturing variance between LEDs.
+///
+/// Each channel of each LED is scaled by `gain / 255` in linear space, on top of the other corrections.
+/// LEDs beyond the end of the table are not calibrated.
+///
+/// # Binary format
+///
+/// Calibration tables are loaded from a binary blob (for example created with [`include_bytes!`]) with the following layout:
+///
+/// | Offset | Size                        | Content                                                         |
+/// |--------|-----------------------------|-----------------------------------------------------------------|
+/// | 0      | 4                           | Magic bytes `LEDC`                                              |
+/// | 4      | 1                           | Format version, currently 1                                     |
+/// | 5      | 1                           | Channel count (1 to 5), must match the driver’s color type      |
+/// | 6      | 2                           | LED count, little-endian                                        |
+/// | 8      | LED count × channel count   | Gains, one byte per channel, LED by LED; 255 is a gain of 1.0   |
+///
+/// The channels of each LED are in the natural order of the color type (see [`Color::channel`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Calibration {
+    gains: &'static [u8],
+    channels: u8,
+}
+
+/// All types of errors that can happen when loading a [`Calibration`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum CalibrationError {
+    /// The data doesn’t start with the magic bytes `LEDC`.
+    InvalidMagic,
+    /// The format version is not supported by this version of the driver.
+    UnsupportedVersion(u8),
+    /// The channel count is zero or larger than five.
+    InvalidChannelCount(u8),
+    /// The data length doesn’t match the header.
+    InvalidLength,
+    /// The channel count of the table doesn’t match the color type of the driver.
+    ChannelMismatch,
+}
+
+impl Calibration {
+    const MAGIC: [u8; 4] = *b"LEDC";
+    const VERSION: u8 = 1;
+    const HEADER_SIZE: usize = 8;
+
+    /// Loads and validates a calibration table in the documented [binary format](Calibration#binary-format).
+    ///
+    /// This function can be evaluated at compile time, so invalid tables can be caught during the build:
+    ///
+    /// ```rust,ignore
+    /// const CALIBRATION: Calibration = match Calibration::from_bytes(include_bytes!("calibration.bin")) {
+    ///     Ok(calibration) => calibration,
+    ///     Err(_) => panic!("invalid calibration table"),
+    /// };
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CalibrationError`] if the data is not a valid calibration table.
+    pub const fn from_bytes(data: &'static [u8]) -> Result<Self, CalibrationError> {
+        let Some((header, gains)) = data.split_at_checked(Self::HEADER_SIZE) else {
+            return Err(CalibrationError::InvalidLength);
+        };
+        if header[0] != Self::MAGIC[0]
+            || header[1] != Self::MAGIC[1]
+            || header[2] != Self::MAGIC[2]
+            || header[3] != Self::MAGIC[3]
+        {
+            return Err(CalibrationError::InvalidMagic);
+        }
+        if header[4] != Self::VERSION {
+            return Err(CalibrationError::UnsupportedVersion(header[4]));
+        }
+        let channels = header[5];
+        if channels == 0 || channels as usize > MAX_CHANNELS {
+            return Err(CalibrationError::InvalidChannelCount(channels));
+        }
+        let led_count = u16::from_le_bytes([header[6], header[7]]) as usize;
+        if gains.len() != led_count * channels as usize {
+            return Err(CalibrationError::InvalidLength);
+        }
+        Ok(Self { gains, channels })
+    }
+
+    /// Number of LEDs in this calibration table.
+    pub const fn led_count(&self) -> usize {
+        self.gains.len() / self.channels as usize
+    }
+
+    /// Number of channels per LED in this calibration table.
+    pub const fn channels(&self) -> u8 {
+        self.channels
+    }
+
+    /// Returns the gain of a channel of an LED, or 255 (no change) if the LED is not part of the table.
+    pub fn gain(&self, index: usize, channel: u8) -> u8 {
+        self.gains
+            .get(index * self.channels as usize + channel as usize)
+            .copied()
+            .unwrap_or(u8::MAX)
+    }
+}
+
+/// Per-driver color correction settings, applied to every color while encoding.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ColorCorrection {
+    pub(crate) gamma: Option<&'static GammaLut>,
+    pub(crate) matrix: Option<CorrectionMatrix>,
+    pub(crate) white_balance: WhiteBalance,
+    pub(crate) calibration: Option<Calibration>,
+    pub(crate) brightness: u8,
+    pub(crate) dithering: bool,
+    /// Frame counter for temporal dithering.
+    frame: u8,
+}
+
+impl ColorCorrection {
+    pub(crate) const fn new() -> Self {
+        Self {
+            gamma: None,
+            matrix: None,
+            white_balance: WhiteBalance::UNCORRECTED,
+            calibration: None,
+            brightness: u8::MAX,
+            dithering: false,
+            frame: 0,
+        }
+    }
+
+    /// Whether applying this correction leaves all colors unchanged.
+    pub(crate) fn is_identity(&self) -> bool {
+        self.gamma.is_none()
+            && self.matrix.is_none()
+            && self.white_balance == WhiteBalance::UNCORRECTED
+            && self.calibration.is_none()
+            && self.brightness == u8::MAX
+            && !self.dithering
+    }
+
+    /// Advances the dithering state to the next frame. Must be called once before encoding each frame.
+    pub(crate) fn next_frame(&mut self) {
+        if self.dithering {
+            self.frame = self.frame.wrapping_add(1);
+        }
+    }
+
+    /// Corrects all channels of the color of the LED at `index`.
+    pub(crate) fn apply<C: Color>(&self, color: &mut C, index: usize) {
+        if self.is_identity() {
+            return;
+        }
+        let linear = self.linearize(color);
+        self.quantize(&linear, color, index);
+    }
+
+    /// Corrects all channels of the color of the LED at `index`, and stores them in an output color of possibly lower precision.
+    ///
+    /// Both colors must have the same number of channels.
+    pub(crate) fn apply_to<In: Color, C: Color>(&self, input: &In, output: &mut C, index: usize) {
+        let linear = self.linearize(input);
+        self.quantize(&linear, output, index);
+    }
+
+    /// Applies the transfer function and the matrix, and returns the linear channel values at 16-bit precision.
+    fn linearize<C: Color>(&self, color: &C) -> [u32; MAX_CHANNELS] {
+        let max = channel_max::<C>();
+        let channels = (C::CHANNELS as usize).min(MAX_CHANNELS);
+
+        let mut linear = [0; MAX_CHANNELS];
+        for (channel, value) in linear.iter_mut().enumerate().take(channels) {
+            let input = color.channel(channel as u8).into() as u64;
+            *value = match self.gamma {
+                Some(gamma) => gamma.lookup(input, max),
+                None => (input * u16::MAX as u64 + max / 2) / max,
+            } as u32;
+        }
+
+        if let Some(matrix) = &self.matrix {
+            matrix.apply(&mut linear, channels);
+        }
+        linear
+    }
+
+    /// Applies the white balance, calibration gains and brightness, and stores the channel values in the color.
+    ///
+    /// Without dithering, values are rounded to the nearest value representable by the channel type.
+    /// With dithering, the rounding threshold changes from frame to frame, so that the average over several frames matches the precise value.
+    fn quantize<C: Color>(&self, linear: &[u32; MAX_CHANNELS], color: &mut C, index: usize) {
+        // All three scale factors are 8-bit fractions.
+        const FACTOR_MAX: u64 = u8::MAX as u64 * u8::MAX as u64 * u8::MAX as u64;
+        let max = channel_max::<C>();
+        let channels = (C::CHANNELS as usize).min(MAX_CHANNELS);
+
+        for (channel, value) in linear.iter().enumerate().take(channels) {
+            let gain = match &self.calibration {
+                Some(calibration) => calibration.gain(index, channel as u8),
+                None => u8::MAX,
+            };
+            let factor =
+                self.white_balance.0[channel] as u64 * gain as u64 * self.brightness as u64;
+            let scaled = (*value as u64 * factor + FACTOR_MAX / 2) / FACTOR_MAX;
+            let threshold = if self.dithering {
+                self.dither_threshold(index, channel)
+            } else {
+                u16::MAX as u64 / 2
+            };
+            let output = (scaled * max + threshold) / u16::MAX as u64;
+            if let Some(output) = C::ChannelType::from_u64(output) {
+                color.set_channel(channel as u8, output);
+            }
+        }
+    }
+
+    /// Rounding threshold for temporal dithering, in `0..u16::MAX`.
+    ///
+    /// The threshold runs through a bit-reversed sequence over 256 frames, so that fractions with few bits (like one half) alternate quickly.
+    /// Every LED and channel starts at a different phase, which spreads the dithering spatially and reduces visible flicker.
+    fn dither_threshold(&self, index: usize, channel: usize) -> u64 {
+        let phase = self
+            .frame
+            .wrapping_add((index as u8).wrapping_mul(37))
+            .wrapping_add((channel as u8).wrapping_mul(91));
+        phase.reverse_bits() as u64 * u16::MAX as u64 / 256
+    }
+}
+
+/// Largest value of a channel of the color type.
+fn channel_max<C: Color>() -> u64 {
+    u64::MAX >> (64 - size_of::<C::ChannelType>() * 8)
+}
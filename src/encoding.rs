@@ -0,0 +1,114 @@
+//! Encoding of channel values into RMT pulses.
+//!
+//! This module is independent of esp-hal and the rest of the crate, so that it can be benchmarked and tested on the host (see `benches/encoding` and `tests/host`).
+
+/// Lookup table from a nibble to its four pulses, most significant bit first.
+///
+/// With this table, a byte is encoded by two copies, instead of a branch for every bit.
+/// A table of whole bytes would be 32 times as large, and was even slower in the host benchmark.
+#[derive(Clone)]
+pub(crate) struct PulseLut<P> {
+    table: [[P; 4]; 16],
+}
+
+impl<P: Copy + PartialEq> PulseLut<P> {
+    /// Builds the table from the pulses of a zero and a one bit.
+    pub(crate) fn new(zero: P, one: P) -> Self {
+        Self {
+            table: core::array::from_fn(|nibble| {
+                core::array::from_fn(|bit| match nibble & (0x8 >> bit) {
+                    0 => zero,
+                    _ => one,
+                })
+            }),
+        }
+    }
+
+    /// Pulse of a zero bit.
+    pub(crate) fn zero(&self) -> P {
+        self.table[0][0]
+    }
+
+    /// Pulse of a one bit.
+    pub(crate) fn one(&self) -> P {
+        self.table[0xf][0]
+    }
+
+    /// Encodes the lowest `out.len()` bits of `value` into `out`, most significant bit first.
+    ///
+    /// The length of `out` must be a multiple of eight.
+    #[inline]
+    pub(crate) fn encode(&self, value: usize, out: &mut [P]) {
+        match out.len() {
+            // Fast path for 8-bit channels, which most LEDs use.
+            8 => {
+                out[..4].copy_from_slice(&self.table[(value >> 4) & 0xf]);
+                out[4..].copy_from_slice(&self.table[value & 0xf]);
+            }
+            _ => {
+                let (nibbles, _) = out.as_chunks_mut::<4>();
+                for (index, nibble) in nibbles.iter_mut().rev().enumerate() {
+                    *nibble = self.table[(value >> (index * 4)) & 0xf];
+                }
+            }
+        }
+    }
+
+    /// Fills `out` with a black frame of up to `led_count` LEDs with `led_pulses` pulses each, followed by the `reset` and `end` pulses.
+    ///
+    /// The frame is shortened to the LEDs that fit into `out`, so that it always ends properly.
+    /// Returns the number of black LEDs, or `None` if `out` can't even hold the `reset` and `end` pulses.
+    pub(crate) fn encode_blank(
+        &self,
+        led_count: usize,
+        led_pulses: usize,
+        reset: P,
+        end: P,
+        out: &mut [P],
+    ) -> Option<usize> {
+        let capacity = out.len().checked_sub(2)? / led_pulses;
+        let led_count = led_count.min(capacity);
+        let frame_end = led_count * led_pulses;
+        out[..frame_end].fill(self.zero());
+        out[frame_end] = reset;
+        out[frame_end + 1] = end;
+        Some(led_count)
+    }
+
+    /// Decodes pulses created by [`Self::encode`] back into a value.
+    pub(crate) fn decode(&self, pulses: &[P]) -> usize {
+        let one = self.one();
+        pulses
+            .iter()
+            .fold(0, |value, pulse| value << 1 | (*pulse == one) as usize)
+    }
+}
+
+/// Writes `preamble` to the start of `buffer`, and lets `encode` write a frame into the rest of it.
+///
+/// If the preamble doesn’t leave room for the `reset` and `end` pulses, `exceeded` is returned.
+/// If the preamble doesn’t fit or `encode` fails, `buffer` starts with an empty frame of only the `reset` and `end` pulses,
+/// so that it neither sends the preamble nor a partial frame.
+pub(crate) fn encode_with_preamble<P: Copy, E>(
+    buffer: &mut [P],
+    preamble: &[P],
+    reset: P,
+    end: P,
+    exceeded: E,
+    encode: impl FnOnce(&mut [P]) -> Result<(), E>,
+) -> Result<(), E> {
+    let result = if preamble.len() + 2 > buffer.len() {
+        Err(exceeded)
+    } else {
+        let (start, rest) = buffer.split_at_mut(preamble.len());
+        start.copy_from_slice(preamble);
+        encode(rest)
+    };
+    if result.is_err()
+        && let [first, second, ..] = buffer
+    {
+        *first = reset;
+        *second = end;
+    }
+    result
+}
@@ -0,0 +1,674 @@
+//! Driving several strips on separate RMT channels with one call.
+//!
+//! When every strip is written on its own, each one starts sending only after the previous one is done,
+//! so at high frame rates, the strips visibly show different frames.
+//! A [`StripGroup`] owns the drivers of several strips, which can differ in length, color type, order and timing.
+//! It encodes the frames of all strips first, and then starts all transmissions one after another, before waiting for any of them:
+//!
+//! ```rust,ignore
+//! let left = Ws2812SmartLeds::<{ buffer_size::<RGB8>(60) }, _>::new(rmt.channel0, peripherals.GPIO2)?;
+//! let right = Sk68xxRgbwSmartLeds::<{ buffer_size::<RGBW<u8>>(30) }, _>::new(rmt.channel1, peripherals.GPIO3)?;
+//! let mut strips = StripGroup::new((left, right));
+//!
+//! strips.write((left_frame, right_frame))?;
+//! ```
+//!
+//! **Limitation:** The transmissions start sequentially, they are not synchronized.
+//! Some ESP32 chips can start several RMT channels in hardware sync, but esp-hal doesn’t expose this yet.
+//! Therefore, the transmissions are started one right after the other in software, usually a few microseconds apart.
+//! An interrupt between two starts delays the remaining strips further.
+//!
+//! Groups of up to eight strips are supported, which is the largest number of transmit channels of any ESP32 chip.
+//! All strips of a group need to be in the same driver mode, either blocking or async.
+//!
+//! ## Segmented strips
+//!
+//! Long installations are often wired as several runs on different pins, for faster updates and power injection.
+//! A [`SegmentedStrip`] makes such runs look like one logical strip:
+//! It implements [`SmartLedsWrite`] (or [`SmartLedsWriteAsync`]), and splits the colors among the runs in order, according to their capacity.
+//! Runs that are wired in the opposite direction can be reversed.
+//!
+//! ```rust,ignore
+//! let first = Ws2812SmartLeds::<{ buffer_size::<RGB8>(500) }, _>::new(rmt.channel0, peripherals.GPIO2)?;
+//! let second = Ws2812SmartLeds::<{ buffer_size::<RGB8>(500) }, _>::new(rmt.channel1, peripherals.GPIO3)?;
+//! let mut strip = SegmentedStrip::new((first, second)).with_reversed(1, true);
+//!
+//! // LEDs 0 to 499 go to the first run, LEDs 500 to 999 to the second run in reverse.
+//! strip.write(effect.frame(1000))?;
+//! ```
+//!
+//! ## Multiplexed pins
+//!
+//! Some chips, like the ESP32-C3, only have two transmit channels. To drive more strips than that,
+//! [`MultiplexedStrips`] lets one channel take turns on several pins, by rerouting its output through the GPIO matrix between frames.
+//! Since the channel doesn’t tell which output signal it uses, the signal of the channel needs to be passed in, like `RMT_SIG_0` for channel 0.
+//! While one pin is selected, the other pins are held at the idle level of the [`Timing`](crate::Timing).
+//! All strips need to be of the same type, and since they are sent one after another, every strip only gets a fraction of the frame rate.
+//!
+//! ```rust,ignore
+//! let driver = Ws2812SmartLeds::<{ buffer_size::<RGB8>(30) }, _>::new(rmt.channel0, NoPin)?;
+//! let pins = [peripherals.GPIO2.into(), peripherals.GPIO3.into(), peripherals.GPIO4.into()];
+//! let mut strips = MultiplexedStrips::new(driver, OutputSignal::RMT_SIG_0, pins);
+//!
+//! for (index, frame) in frames.into_iter().enumerate() {
+//!     strips.write_to(index, frame)?;
+//! }
+//! ```
+
+use core::{future::poll_fn, pin::pin, task::Poll};
+
+use esp_hal::{
+    Async, Blocking, DriverMode,
+    gpio::{AnyPin, Output, OutputConfig, OutputSignal, interconnect},
+    rmt::PulseCode,
+};
+use smart_leds_trait::{SmartLedsWrite, SmartLedsWriteAsync};
+
+use crate::{AdapterError, Color, ColorOrder, GenericRmtSmartLeds, Transmission, levels};
+
+/// Several strips on separate RMT channels, whose transmissions are started one after another.
+///
+/// The starts are sequential and not synchronized, so the strips start a few microseconds apart; see the [module documentation](self) for details.
+/// `Strips` is a tuple of drivers.
+pub struct StripGroup<Strips> {
+    strips: Strips,
+}
+
+impl<Strips> StripGroup<Strips> {
+    /// Combines the given tuple of drivers into a group.
+    pub const fn new(strips: Strips) -> Self {
+        Self { strips }
+    }
+
+    /// Returns a reference to the drivers.
+    pub const fn strips(&self) -> &Strips {
+        &self.strips
+    }
+
+    /// Returns a mutable reference to the drivers.
+    ///
+    /// Use this to configure the drivers, or to [`prepare`](GenericRmtSmartLeds::prepare) frames of only some strips before calling `flush`.
+    pub const fn strips_mut(&mut self) -> &mut Strips {
+        &mut self.strips
+    }
+
+    /// Splits the group into its drivers.
+    pub fn into_inner(self) -> Strips {
+        self.strips
+    }
+}
+
+/// Several strips on separate RMT channels that are addressed as one logical strip.
+///
+/// `Strips` is a tuple of drivers with the same color type. See the [module documentation](self#segmented-strips) for details.
+pub struct SegmentedStrip<Strips> {
+    group: StripGroup<Strips>,
+    /// Bit mask of the reversed segments.
+    reversed: u8,
+}
+
+impl<Strips> SegmentedStrip<Strips> {
+    /// Combines the given tuple of drivers into a logical strip, in the order of the tuple.
+    ///
+    /// Every driver receives as many colors as its [`capacity`](GenericRmtSmartLeds::capacity).
+    pub const fn new(strips: Strips) -> Self {
+        Self {
+            group: StripGroup::new(strips),
+            reversed: 0,
+        }
+    }
+
+    /// Sets whether the segment with the given index is wired in the opposite direction.
+    ///
+    /// The first color of a reversed segment is sent to its last LED.
+    /// Reversed segments are always sent in full, so LEDs without a color are switched off.
+    /// Per-pixel calibration of a reversed segment is applied in the order of the colors, so the calibration table needs to be reversed as well.
+    pub const fn with_reversed(mut self, segment: usize, reversed: bool) -> Self {
+        assert!(segment < 8, "segment index out of range");
+        if reversed {
+            self.reversed |= 1 << segment;
+        } else {
+            self.reversed &= !(1 << segment);
+        }
+        self
+    }
+
+    /// Returns whether the segment with the given index is reversed.
+    pub const fn is_reversed(&self, segment: usize) -> bool {
+        segment < 8 && self.reversed & (1 << segment) != 0
+    }
+
+    /// Returns a reference to the drivers.
+    pub const fn strips(&self) -> &Strips {
+        self.group.strips()
+    }
+
+    /// Returns a mutable reference to the drivers.
+    pub const fn strips_mut(&mut self) -> &mut Strips {
+        self.group.strips_mut()
+    }
+
+    /// Splits the logical strip into its drivers.
+    pub fn into_inner(self) -> Strips {
+        self.group.into_inner()
+    }
+}
+
+/// Several strips on separate pins that share one RMT channel by taking turns.
+///
+/// Writing sends the colors to the strip on the selected pin. See the [module documentation](self#multiplexed-pins) for details.
+pub struct MultiplexedStrips<'d, const PINS: usize, Buffer, Mode, C, Order, Timing>
+where
+    Buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+    Mode: DriverMode,
+    C: Color,
+    Order: ColorOrder<C>,
+    Timing: crate::Timing,
+{
+    driver: GenericRmtSmartLeds<'d, Buffer, Mode, C, Order, Timing>,
+    /// Output signal of the RMT channel of the driver.
+    signal: OutputSignal,
+    pins: [AnyPin<'d>; PINS],
+    selected: usize,
+}
+
+impl<'d, const PINS: usize, Buffer, Mode, C, Order, Timing>
+    MultiplexedStrips<'d, PINS, Buffer, Mode, C, Order, Timing>
+where
+    Buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+    Mode: DriverMode,
+    C: Color,
+    Order: ColorOrder<C>,
+    Timing: crate::Timing,
+{
+    /// Combines a driver with the pins that it drives in turns, and selects the first pin.
+    ///
+    /// The driver should be created without a pin, using [`NoPin`](esp_hal::gpio::NoPin).
+    /// `signal` is the output signal of its RMT channel in the GPIO matrix, like [`OutputSignal::RMT_SIG_0`] for channel 0.
+    pub fn new(
+        driver: GenericRmtSmartLeds<'d, Buffer, Mode, C, Order, Timing>,
+        signal: OutputSignal,
+        pins: [AnyPin<'d>; PINS],
+    ) -> Self {
+        const { assert!(PINS > 0, "at least one pin is required") };
+        let mut strips = Self {
+            driver,
+            signal,
+            pins,
+            selected: 0,
+        };
+        for index in 1..PINS {
+            strips.release(index);
+        }
+        strips.connect(0);
+        strips
+    }
+
+    /// Routes the output of the RMT channel to the pin with the given index, so that the next write goes to its strip.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is not smaller than the number of pins.
+    pub fn select(&mut self, index: usize) {
+        assert!(index < PINS, "pin index out of range");
+        if index != self.selected {
+            self.release(self.selected);
+            self.connect(index);
+            self.selected = index;
+        }
+    }
+
+    /// Returns the index of the selected pin.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Connects the pin with the given index to the RMT channel.
+    fn connect(&mut self, index: usize) {
+        // The route is set up directly in the GPIO matrix, the same way esp-hal connects a channel to its pin.
+        // It doesn’t belong to a channel, so it stays in place until the pin is released.
+        let pin = interconnect::OutputSignal::from(self.pins[index].reborrow());
+        pin.apply_output_config(&OutputConfig::default());
+        pin.set_output_enable(true);
+        self.signal.connect_to(&pin);
+    }
+
+    /// Disconnects the pin with the given index from the RMT channel, and holds it at the idle level.
+    fn release(&mut self, index: usize) {
+        self.signal.disconnect_from(&self.pins[index].reborrow());
+        let _ = Output::new(
+            self.pins[index].reborrow(),
+            levels::<Timing>().1,
+            OutputConfig::default(),
+        );
+    }
+
+    /// Returns a reference to the driver.
+    pub fn driver(&self) -> &GenericRmtSmartLeds<'d, Buffer, Mode, C, Order, Timing> {
+        &self.driver
+    }
+
+    /// Returns a mutable reference to the driver.
+    ///
+    /// Configuration like [`set_brightness`](GenericRmtSmartLeds::set_brightness) applies to all strips.
+    pub fn driver_mut(&mut self) -> &mut GenericRmtSmartLeds<'d, Buffer, Mode, C, Order, Timing> {
+        &mut self.driver
+    }
+
+    /// Splits the multiplexer into its driver and pins.
+    pub fn into_inner(
+        self,
+    ) -> (
+        GenericRmtSmartLeds<'d, Buffer, Mode, C, Order, Timing>,
+        [AnyPin<'d>; PINS],
+    ) {
+        (self.driver, self.pins)
+    }
+}
+
+impl<'d, const PINS: usize, Buffer, C, Order, Timing>
+    MultiplexedStrips<'d, PINS, Buffer, Blocking, C, Order, Timing>
+where
+    Buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+    C: Color,
+    Order: ColorOrder<C>,
+    Timing: crate::Timing,
+{
+    /// Selects the pin with the given index, and writes the colors to its strip.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is not smaller than the number of pins.
+    pub fn write_to<T, I>(&mut self, index: usize, iterator: T) -> Result<(), AdapterError>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<C>,
+    {
+        self.select(index);
+        self.driver.write(iterator)
+    }
+}
+
+impl<'d, const PINS: usize, Buffer, C, Order, Timing> SmartLedsWrite
+    for MultiplexedStrips<'d, PINS, Buffer, Blocking, C, Order, Timing>
+where
+    Buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+    C: Color,
+    Order: ColorOrder<C>,
+    Timing: crate::Timing,
+{
+    type Error = AdapterError;
+    type Color = C;
+
+    /// Writes the colors to the strip on the selected pin.
+    fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        self.driver.write(iterator)
+    }
+}
+
+impl<'d, const PINS: usize, Buffer, C, Order, Timing>
+    MultiplexedStrips<'d, PINS, Buffer, Async, C, Order, Timing>
+where
+    Buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+    C: Color,
+    Order: ColorOrder<C>,
+    Timing: crate::Timing,
+{
+    /// Selects the pin with the given index, and writes the colors to its strip.
+    ///
+    /// Like the driver, this selects the pin and prepares the buffer immediately, and only transmits once awaited.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is not smaller than the number of pins.
+    pub fn write_to<T, I>(
+        &mut self,
+        index: usize,
+        iterator: T,
+    ) -> impl Future<Output = Result<(), AdapterError>>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<C>,
+    {
+        self.select(index);
+        self.driver.write(iterator)
+    }
+}
+
+impl<'d, const PINS: usize, Buffer, C, Order, Timing> SmartLedsWriteAsync
+    for MultiplexedStrips<'d, PINS, Buffer, Async, C, Order, Timing>
+where
+    Buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+    C: Color,
+    Order: ColorOrder<C>,
+    Timing: crate::Timing,
+{
+    type Error = AdapterError;
+    type Color = C;
+
+    /// Writes the colors to the strip on the selected pin.
+    fn write<T, I>(&mut self, iterator: T) -> impl Future<Output = Result<(), Self::Error>>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        self.driver.write(iterator)
+    }
+}
+
+/// Implements writing for segmented strips of one tuple size. The arguments are the tuple index of every segment,
+/// followed by names for the generic parameters of its driver.
+macro_rules! impl_segmented_strip {
+    ($(($index:tt, $buffer:ident, $order:ident, $timing:ident)),+) => {
+        impl<'d, Mode, C, $($buffer, $order, $timing),+>
+            SegmentedStrip<($(GenericRmtSmartLeds<'d, $buffer, Mode, C, $order, $timing>,)+)>
+        where
+            Mode: DriverMode,
+            C: Color,
+            $(
+                $buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+                $order: ColorOrder<C>,
+                $timing: crate::Timing,
+            )+
+        {
+            /// Returns the total number of LEDs of all segments.
+            pub fn len(&self) -> usize {
+                0 $(+ self.group.strips.$index.capacity())+
+            }
+
+            /// Returns whether the segments have no LEDs at all.
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+
+            /// Splits the colors among the segments and encodes them, without sending them.
+            ///
+            /// # Errors
+            ///
+            /// Returns [`AdapterError::BufferSizeExceeded`] if there are more colors than LEDs.
+            pub fn prepare<T, I>(&mut self, iterator: T) -> Result<(), AdapterError>
+            where
+                T: IntoIterator<Item = I>,
+                I: Into<C>,
+            {
+                let mut colors = iterator.into_iter();
+                $(
+                    let segment = &mut self.group.strips.$index;
+                    let length = segment.capacity();
+                    segment.prepare(colors.by_ref().take(length))?;
+                    if self.reversed & (1 << $index) != 0 {
+                        segment.reverse_frame(length)?;
+                    }
+                )+
+                match colors.next() {
+                    Some(_) => Err(AdapterError::BufferSizeExceeded),
+                    None => Ok(()),
+                }
+            }
+        }
+
+        impl<'d, C, $($buffer, $order, $timing),+> SmartLedsWrite
+            for SegmentedStrip<($(GenericRmtSmartLeds<'d, $buffer, Blocking, C, $order, $timing>,)+)>
+        where
+            C: Color,
+            $(
+                $buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+                $order: ColorOrder<C>,
+                $timing: crate::Timing,
+            )+
+        {
+            type Error = AdapterError;
+            type Color = C;
+
+            /// Splits the colors among the segments, then starts their transmissions one after another, like [`StripGroup::flush`].
+            fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+            where
+                T: IntoIterator<Item = I>,
+                I: Into<Self::Color>,
+            {
+                self.prepare(iterator)?;
+                self.group.flush()
+            }
+        }
+
+        impl<'d, C, $($buffer, $order, $timing),+> SmartLedsWriteAsync
+            for SegmentedStrip<($(GenericRmtSmartLeds<'d, $buffer, Async, C, $order, $timing>,)+)>
+        where
+            C: Color,
+            $(
+                $buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+                $order: ColorOrder<C>,
+                $timing: crate::Timing,
+            )+
+        {
+            type Error = AdapterError;
+            type Color = C;
+
+            /// Splits the colors among the segments, then starts their transmissions one after another, like [`StripGroup::flush`].
+            ///
+            /// Like the drivers, this prepares the buffers immediately and only transmits once awaited.
+            fn write<T, I>(&mut self, iterator: T) -> impl Future<Output = Result<(), Self::Error>>
+            where
+                T: IntoIterator<Item = I>,
+                I: Into<Self::Color>,
+            {
+                let res = self.prepare(iterator);
+                async move {
+                    res?;
+                    self.group.flush().await
+                }
+            }
+        }
+    };
+}
+
+impl_segmented_strip!((0, B0, O0, T0));
+impl_segmented_strip!((0, B0, O0, T0), (1, B1, O1, T1));
+impl_segmented_strip!((0, B0, O0, T0), (1, B1, O1, T1), (2, B2, O2, T2));
+impl_segmented_strip!(
+    (0, B0, O0, T0),
+    (1, B1, O1, T1),
+    (2, B2, O2, T2),
+    (3, B3, O3, T3)
+);
+impl_segmented_strip!(
+    (0, B0, O0, T0),
+    (1, B1, O1, T1),
+    (2, B2, O2, T2),
+    (3, B3, O3, T3),
+    (4, B4, O4, T4)
+);
+impl_segmented_strip!(
+    (0, B0, O0, T0),
+    (1, B1, O1, T1),
+    (2, B2, O2, T2),
+    (3, B3, O3, T3),
+    (4, B4, O4, T4),
+    (5, B5, O5, T5)
+);
+impl_segmented_strip!(
+    (0, B0, O0, T0),
+    (1, B1, O1, T1),
+    (2, B2, O2, T2),
+    (3, B3, O3, T3),
+    (4, B4, O4, T4),
+    (5, B5, O5, T5),
+    (6, B6, O6, T6)
+);
+impl_segmented_strip!(
+    (0, B0, O0, T0),
+    (1, B1, O1, T1),
+    (2, B2, O2, T2),
+    (3, B3, O3, T3),
+    (4, B4, O4, T4),
+    (5, B5, O5, T5),
+    (6, B6, O6, T6),
+    (7, B7, O7, T7)
+);
+
+/// Implements writing for groups of one tuple size. The arguments are the tuple index of every strip,
+/// followed by names for the generic parameters of its driver, and of the frame it is written with.
+macro_rules! impl_strip_group {
+    ($(($index:tt, $buffer:ident, $color:ident, $order:ident, $timing:ident, $frame:ident, $item:ident)),+) => {
+        impl<'d, $($buffer, $color, $order, $timing),+>
+            StripGroup<($(GenericRmtSmartLeds<'d, $buffer, Blocking, $color, $order, $timing>,)+)>
+        where
+            $(
+                $buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+                $color: Color,
+                $order: ColorOrder<$color>,
+                $timing: crate::Timing,
+            )+
+        {
+            /// Encodes one frame for every strip, then starts their transmissions one after another.
+            ///
+            /// The starts are sequential and not synchronized; see the [module documentation](self).
+            ///
+            /// # Errors
+            ///
+            /// Returns the first error of any strip. If a frame can’t be encoded, nothing is transmitted.
+            pub fn write<$($frame, $item),+>(&mut self, frames: ($($frame,)+)) -> Result<(), AdapterError>
+            where
+                $($frame: IntoIterator<Item = $item>, $item: Into<$color>,)+
+            {
+                $(self.strips.$index.prepare(frames.$index)?;)+
+                self.flush()
+            }
+
+            /// Starts transmitting the existing data of all strips one after another, then waits for all of them.
+            ///
+            /// The starts are sequential and not synchronized; see the [module documentation](self).
+            ///
+            /// # Errors
+            ///
+            /// Returns the first error of any strip. The other strips are transmitted regardless.
+            pub fn flush(&mut self) -> Result<(), AdapterError> {
+                let mut transmissions = ($(self.strips.$index.start_transmit(),)+);
+                // Waiting for one transmission only refills the RAM of its own channel, so all of them are
+                // polled in turn until every one is done. Otherwise, longer frames of the other strips underrun.
+                loop {
+                    let mut done = true;
+                    $(
+                        if let Ok(transmission) = &mut transmissions.$index {
+                            done &= transmission.poll();
+                        }
+                    )+
+                    if done {
+                        break;
+                    }
+                }
+                let mut result = Ok(());
+                $(result = result.and(transmissions.$index.and_then(Transmission::finish));)+
+                result
+            }
+        }
+
+        impl<'d, $($buffer, $color, $order, $timing),+>
+            StripGroup<($(GenericRmtSmartLeds<'d, $buffer, Async, $color, $order, $timing>,)+)>
+        where
+            $(
+                $buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+                $color: Color,
+                $order: ColorOrder<$color>,
+                $timing: crate::Timing,
+            )+
+        {
+            /// Encodes one frame for every strip, then starts their transmissions one after another.
+            ///
+            /// The starts are sequential and not synchronized; see the [module documentation](self).
+            ///
+            /// # Errors
+            ///
+            /// Returns the first error of any strip. If a frame can’t be encoded, nothing is transmitted.
+            pub async fn write<$($frame, $item),+>(&mut self, frames: ($($frame,)+)) -> Result<(), AdapterError>
+            where
+                $($frame: IntoIterator<Item = $item>, $item: Into<$color>,)+
+            {
+                $(self.strips.$index.prepare(frames.$index)?;)+
+                self.flush().await
+            }
+
+            /// Starts transmitting the existing data of all strips one after another, then waits for all of them.
+            ///
+            /// The starts are sequential and not synchronized; see the [module documentation](self).
+            ///
+            /// # Errors
+            ///
+            /// Returns the first error of any strip. The other strips are transmitted regardless.
+            pub async fn flush(&mut self) -> Result<(), AdapterError> {
+                let mut transmissions = ($(Some(pin!(self.strips.$index.transmit_async(Ok(())))),)+);
+                let mut result = Ok(());
+                // Polling all transmissions in turn starts them one after another on the first poll.
+                poll_fn(|cx| {
+                    let mut pending = false;
+                    $(
+                        if let Some(transmission) = &mut transmissions.$index {
+                            match transmission.as_mut().poll(cx) {
+                                Poll::Ready(strip_result) => {
+                                    result = result.and(strip_result);
+                                    transmissions.$index = None;
+                                }
+                                Poll::Pending => pending = true,
+                            }
+                        }
+                    )+
+                    if pending { Poll::Pending } else { Poll::Ready(()) }
+                })
+                .await;
+                result
+            }
+        }
+    };
+}
+
+impl_strip_group!((0, B0, C0, O0, T0, F0, I0));
+impl_strip_group!((0, B0, C0, O0, T0, F0, I0), (1, B1, C1, O1, T1, F1, I1));
+impl_strip_group!(
+    (0, B0, C0, O0, T0, F0, I0),
+    (1, B1, C1, O1, T1, F1, I1),
+    (2, B2, C2, O2, T2, F2, I2)
+);
+impl_strip_group!(
+    (0, B0, C0, O0, T0, F0, I0),
+    (1, B1, C1, O1, T1, F1, I1),
+    (2, B2, C2, O2, T2, F2, I2),
+    (3, B3, C3, O3, T3, F3, I3)
+);
+impl_strip_group!(
+    (0, B0, C0, O0, T0, F0, I0),
+    (1, B1, C1, O1, T1, F1, I1),
+    (2, B2, C2, O2, T2, F2, I2),
+    (3, B3, C3, O3, T3, F3, I3),
+    (4, B4, C4, O4, T4, F4, I4)
+);
+impl_strip_group!(
+    (0, B0, C0, O0, T0, F0, I0),
+    (1, B1, C1, O1, T1, F1, I1),
+    (2, B2, C2, O2, T2, F2, I2),
+    (3, B3, C3, O3, T3, F3, I3),
+    (4, B4, C4, O4, T4, F4, I4),
+    (5, B5, C5, O5, T5, F5, I5)
+);
+impl_strip_group!(
+    (0, B0, C0, O0, T0, F0, I0),
+    (1, B1, C1, O1, T1, F1, I1),
+    (2, B2, C2, O2, T2, F2, I2),
+    (3, B3, C3, O3, T3, F3, I3),
+    (4, B4, C4, O4, T4, F4, I4),
+    (5, B5, C5, O5, T5, F5, I5),
+    (6, B6, C6, O6, T6, F6, I6)
+);
+impl_strip_group!(
+    (0, B0, C0, O0, T0, F0, I0),
+    (1, B1, C1, O1, T1, F1, I1),
+    (2, B2, C2, O2, T2, F2, I2),
+    (3, B3, C3, O3, T3, F3, I3),
+    (4, B4, C4, O4, T4, F4, I4),
+    (5, B5, C5, O5, T5, F5, I5),
+    (6, B6, C6, O6, T6, F6, I6),
+    (7, B7, C7, O7, T7, F7, I7)
+);
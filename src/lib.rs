@@ -25,110 +25,82 @@
 //! which takes an RMT channel and a [`PeripheralOutput`].
 //! If you want to reuse the channel afterwards, you can use [`esp_hal::rmt::ChannelCreator::reborrow`] to create a shorter-lived derived channel.
 //! [`RmtSmartLeds`] is configured at compile-time to support a variety of LED configurations. See the documentation for [`RmtSmartLeds`] for more info.
+//! The [`smartleds!`] macro names a [`RmtSmartLeds`] type directly from the LED count, so that you don’t need to compute the buffer size yourself.
+//! If the number of LEDs is only known at runtime, [`SliceRmtSmartLeds`] works the same way, but with a borrowed buffer.
+//!
+//! To drive several strips on separate RMT channels with one call, combine their drivers in a [`group::StripGroup`],
+//! or in a [`group::SegmentedStrip`] to address them as one long strip.
+//! To drive more strips than there are RMT channels, [`group::MultiplexedStrips`] lets one channel take turns on several pins.
+//!
+//! To drive RGBW LEDs with RGB colors, wrap the driver in an [`rgbw::RgbwAdapter`].
+//! Tunable-white colors for a given color temperature can be created with the [`color_temperature`] helpers.
 //!
 //! ## Features
 //!
 //! - `defmt`: Derive [`defmt::Format`] on some types.
+//! - `alloc`: Provide [`VecRmtSmartLeds`], which stores its buffer on the heap and can be resized at runtime. This requires a global allocator, like `esp-alloc`.
 //!
 //! Other features provided by this crate are not for external use, they are only used for testing and examples.
 #![doc(html_logo_url = "https://avatars.githubusercontent.com/u/46717278")]
 #![deny(missing_docs)]
 #![no_std]
 
-use core::{fmt::Debug, marker::PhantomData};
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::{fmt::Debug, future::poll_fn, marker::PhantomData, pin::pin, task::Poll};
+
+mod color;
+pub mod color_temperature;
+pub mod correction;
+mod encoding;
+pub mod group;
+mod math;
+pub mod power;
+pub mod rgbw;
+mod timing;
 
+pub use color::Color;
 pub use color_order::ColorOrder;
+use correction::{
+    Calibration, CalibrationError, ColorCorrection, CorrectionMatrix, GammaLut, WhiteBalance,
+};
+use encoding::{PulseLut, encode_with_preamble};
 use esp_hal::{
     Async, Blocking, DriverMode,
     clock::Clocks,
     gpio::{Level, interconnect::PeripheralOutput},
     rmt::{
         Channel, ConfigError as RmtConfigError, Error as RmtError, PulseCode, Tx, TxChannelConfig,
-        TxChannelCreator,
+        TxChannelCreator, TxTransaction,
     },
+    time::{Duration, Instant},
 };
-use num_traits::Unsigned;
-use smart_leds_trait::{
-    CctWhite, RGB, RGB8, RGBCCT, RGBW, SmartLedsWrite, SmartLedsWriteAsync, White,
+use power::{ChannelSums, PowerLimiter, PowerModel, PowerReport, ThermalLimit};
+use smart_leds_trait::{RGB8, RGBW, SmartLedsWrite, SmartLedsWriteAsync, White};
+pub use timing::{
+    Apa106Timing, Gs8208Timing, Sk68xxTiming, Sm16703Timing, Timing, Tm1829Timing, Ucs1903Timing,
+    Ws2811LowSpeedTiming, Ws2811Timing, Ws2812Timing, Ws2812bTiming, Ws2812bV5Timing, Ws2813Timing,
+    Ws2814Timing, Ws2815Timing,
 };
 
-/// Common trait for all different smart LED dependent timings.
-///
-/// All common smart LEDs are controlled by sending PWM-like pulses, in two different configurations for high and low.
-/// The required timings (and tolerances) can be found in the relevant datasheets.
-///
-/// Provided timings: [`Sk68xxTiming`], [`Ws2812bTiming`], [`Ws2811Timing`], [`Ws2812Timing`]
-// Implementations of this should be vacant enums so they can’t be constructed.
-pub trait Timing {
-    /// Low time for zero pulse, in nanoseconds.
-    const TIME_0_LOW: u16;
-    /// High time for zero pulse, in nanoseconds.
-    const TIME_0_HIGH: u16;
-    /// Low time for one pulse, in nanoseconds.
-    const TIME_1_LOW: u16;
-    /// High time for one pulse, in nanoseconds.
-    const TIME_1_HIGH: u16;
-}
-
-const SK68XX_CODE_PERIOD: u16 = 1200;
-/// Timing for the SK68 collection of LEDs.
-pub enum Sk68xxTiming {}
-impl Timing for Sk68xxTiming {
-    const TIME_0_HIGH: u16 = 320;
-    const TIME_0_LOW: u16 = SK68XX_CODE_PERIOD - Self::TIME_0_HIGH;
-    const TIME_1_HIGH: u16 = 640;
-    const TIME_1_LOW: u16 = SK68XX_CODE_PERIOD - Self::TIME_1_HIGH;
-}
-
-/// Timing for the WS2812B LEDs.
-pub enum Ws2812bTiming {}
-impl Timing for Ws2812bTiming {
-    const TIME_0_HIGH: u16 = 400;
-    const TIME_0_LOW: u16 = 800;
-    const TIME_1_HIGH: u16 = 850;
-    const TIME_1_LOW: u16 = 450;
-}
-
-/// Timing for the WS2812 LEDs.
-pub enum Ws2812Timing {}
-impl Timing for Ws2812Timing {
-    const TIME_0_HIGH: u16 = 350;
-    const TIME_0_LOW: u16 = 700;
-    const TIME_1_HIGH: u16 = 800;
-    const TIME_1_LOW: u16 = 600;
-}
-
-/// Timing for the WS2811 driver ICs, low-speed mode.
-pub enum Ws2811LowSpeedTiming {}
-impl Timing for Ws2811LowSpeedTiming {
-    const TIME_0_HIGH: u16 = 500;
-    const TIME_0_LOW: u16 = 2000;
-    const TIME_1_HIGH: u16 = 1200;
-    const TIME_1_LOW: u16 = 1300;
-}
-
-/// Timing for the WS2811 driver ICs, high-speed mode.
-pub enum Ws2811Timing {}
-impl Timing for Ws2811Timing {
-    const TIME_0_HIGH: u16 = Ws2811LowSpeedTiming::TIME_0_HIGH / 2;
-    const TIME_0_LOW: u16 = Ws2811LowSpeedTiming::TIME_0_LOW / 2;
-    const TIME_1_HIGH: u16 = Ws2811LowSpeedTiming::TIME_1_HIGH / 2;
-    const TIME_1_LOW: u16 = Ws2811LowSpeedTiming::TIME_1_LOW / 2;
-}
-
 /// All types of errors that can happen during the conversion and transmission
 /// of LED commands.
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum AdapterError {
-    /// Raised in the event that the RMT buffer is not large enough.
+    /// Raised in the event that the RMT buffer is not large enough, and the [`Overflow`] policy is [`Overflow::Error`].
     ///
-    /// This almost always points to an issue with the `BUFFER_SIZE` parameter of [`RmtSmartLeds`].
+    /// This almost always points to an issue with the `BUFFER_SIZE` parameter of [`RmtSmartLeds`], or the length of the buffer of a [`SliceRmtSmartLeds`].
     /// You should create this parameter using [`buffer_size`], passing in the desired number of LEDs that will be controlled.
     BufferSizeExceeded,
     /// Raised if something goes wrong in the transmission. This contains the inner HAL error ([`RmtError`]).
     TransmissionError(RmtError),
+    /// Raised if raw data doesn’t consist of whole LEDs, i.e. its length is not a multiple of the size of the color type.
+    InvalidDataLength,
 }
 
 impl From<RmtError> for AdapterError {
@@ -137,60 +109,50 @@ impl From<RmtError> for AdapterError {
     }
 }
 
-/// Utility trait that retrieves metadata about all [`smart_leds_trait`] color types.
-pub trait Color {
-    /// The maximum channel number this color supports.
+/// What happens when a write provides more colors than the buffer can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Overflow {
+    /// Return [`AdapterError::BufferSizeExceeded`]. Nothing is transmitted.
     ///
-    /// - For RGB (or any permutation thereof), this is 3.
-    /// - For RGBW, this is 4.
-    /// - For RGBCCT, this is 5.
-    /// - For CCT, this is 2.
+    /// The buffer is left with an empty frame, so a later flush doesn’t send the colors that were encoded before the error.
+    #[default]
+    Error,
+    /// Send the colors that fit, and silently ignore the rest.
+    Truncate,
+    /// Send the colors that fit, and count the rest, which is reported in [`WriteReport::dropped`].
     ///
-    /// Note that this channel count is used by users of [`ColorOrder`] to limit the channel number that’s passed into [`ColorOrder::get_channel_data`].
-    const CHANNELS: u8;
-
-    /// Type of a single channel of this color. Usually [`u8`], but [`u16`] is also used for some LEDs.
-    type ChannelType: Unsigned + Into<usize>;
-}
-
-impl<T> Color for RGB<T>
-where
-    T: Unsigned + Into<usize>,
-{
-    const CHANNELS: u8 = 3;
-    type ChannelType = T;
-}
-
-impl<T> Color for RGBW<T>
-where
-    T: Unsigned + Into<usize>,
-{
-    const CHANNELS: u8 = 4;
-    type ChannelType = T;
-}
-
-impl<T> Color for RGBCCT<T>
-where
-    T: Unsigned + Into<usize>,
-{
-    const CHANNELS: u8 = 5;
-    type ChannelType = T;
+    /// Unlike [`Overflow::Truncate`], this consumes the entire input.
+    Clamp,
 }
 
-impl<T> Color for White<T>
-where
-    T: Unsigned + Into<usize>,
-{
-    const CHANNELS: u8 = 1;
-    type ChannelType = T;
+/// What happens when a write provides fewer colors than the buffer can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Underflow {
+    /// Only send the provided colors, so the remaining LEDs keep their previous colors.
+    #[default]
+    Leave,
+    /// Switch off the remaining LEDs.
+    Blank,
+    /// Repeat the provided colors until the buffer is full.
+    ///
+    /// The repetitions are copies of the corrected colors, so per-pixel calibration is only applied according to the pattern’s positions.
+    /// If no colors are provided, this behaves like [`Underflow::Leave`].
+    Repeat,
 }
 
-impl<T> Color for CctWhite<T>
-where
-    T: Unsigned + Into<usize>,
-{
-    const CHANNELS: u8 = 2;
-    type ChannelType = T;
+/// Number of LEDs handled by the last write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct WriteReport {
+    /// Number of colors from the input that were encoded.
+    pub encoded: usize,
+    /// Number of LEDs after the input that were filled according to the [`Underflow`] policy.
+    pub filled: usize,
+    /// Number of colors from the input that didn’t fit into the buffer. This is only counted with [`Overflow::Clamp`].
+    pub dropped: usize,
 }
 
 /// Calculate the required buffer size for a certain number of LEDs.
@@ -199,21 +161,49 @@ where
 /// Attempting to use more LEDs that the buffer is configured for will result in
 /// an [`AdapterError::BufferSizeExceeded`] error.
 ///
-/// You need to specify the correct color and channel type
-// TODO: As soon as generic expressions are more stabilized, we should be able to do this calculation entirely internally in `RmtSmartLeds`. For now, users have to be careful.
+/// You need to specify the correct color and channel type.
+/// The [`smartleds!`] macro does this for you, given the LED count.
+// TODO: As soon as generic expressions are more stabilized, we should be able to do this calculation entirely internally in `RmtSmartLeds`. For now, users have to be careful or use `smartleds!`.
 pub const fn buffer_size<C: Color>(led_count: usize) -> usize {
     // The size we're assigning here is calculated as following
     //  (
     //   Nr. of LEDs
     //   * channels
     //   * pulses per channel (=bitcount)
-    //  ) + 1 additional pulse for the end delimiter
-    led_count * (size_of::<C::ChannelType>() * 8) * C::CHANNELS as usize + 1
+    //  ) + 1 additional pulse for the reset period
+    //    + 1 additional pulse for the end delimiter
+    led_count * (size_of::<C::ChannelType>() * 8) * C::CHANNELS as usize + 2
+}
+
+/// Names a [`RmtSmartLeds`] type for the given color type, LED count, [`ColorOrder`] and [`Timing`], computing the buffer size internally.
+///
+/// ```rust,ignore
+/// let mut led = <smartleds!(RGB8, 60, color_order::Grb, Ws2812Timing)>::new(rmt.channel0, peripherals.GPIO2)?;
+/// ```
+///
+/// Without further arguments, the lifetime and driver mode are inferred, which works for local variables.
+/// To name the type in a struct field or a `static`, pass them after the timing:
+///
+/// ```rust,ignore
+/// struct Lights {
+///     strip: smartleds!(RGB8, 60, color_order::Grb, Ws2812Timing, 'static, Async),
+/// }
+/// ```
+///
+/// Use [`RmtSmartLeds::write_array`] to check at compile time that a write fits into the strip.
+#[macro_export]
+macro_rules! smartleds {
+    ($color:ty, $led_count:expr, $order:ty, $timing:ty $(,)?) => {
+        $crate::RmtSmartLeds<'_, { $crate::buffer_size::<$color>($led_count) }, _, $color, $order, $timing>
+    };
+    ($color:ty, $led_count:expr, $order:ty, $timing:ty, $lifetime:lifetime, $mode:ty $(,)?) => {
+        $crate::RmtSmartLeds<$lifetime, { $crate::buffer_size::<$color>($led_count) }, $mode, $color, $order, $timing>
+    };
 }
 
 /// Common [`ColorOrder`] implementations.
 pub mod color_order {
-    use num_traits::Unsigned;
+    use num_traits::{FromPrimitive, Unsigned};
     use smart_leds_trait::{RGB, RGBW, White};
 
     use crate::Color;
@@ -239,7 +229,7 @@ pub mod color_order {
             pub enum $name {}
             impl<T> ColorOrder<RGB<T>> for $name
             where
-                T: Copy + Unsigned + Into<usize>,
+                T: Copy + Unsigned + Into<usize> + FromPrimitive,
             {
                 fn get_channel_data(color: &RGB<T>, channel: u8) -> T {
                     match channel {
@@ -264,7 +254,7 @@ pub mod color_order {
     pub enum Rgbw {}
     impl<T> ColorOrder<RGBW<T>> for Rgbw
     where
-        T: Copy + Unsigned + Into<usize>,
+        T: Copy + Unsigned + Into<usize> + FromPrimitive,
     {
         fn get_channel_data(color: &RGBW<T>, channel: u8) -> T {
             match channel {
@@ -281,7 +271,7 @@ pub mod color_order {
     pub enum SingleChannel {}
     impl<T> ColorOrder<White<T>> for SingleChannel
     where
-        T: Copy + Unsigned + Into<usize>,
+        T: Copy + Unsigned + Into<usize> + FromPrimitive,
     {
         fn get_channel_data(color: &White<T>, _channel: u8) -> T {
             color.0
@@ -295,6 +285,7 @@ pub mod color_order {
 ///
 /// This type supports many configurations of color order, LED timings, and LED count. For this reason, there are three main type parameters you have to choose:
 /// - The buffer size. This determines how many RMT pulses can be sent by this driver, and allows it to function entirely without heap allocation. It is strongly recommended to use the [`buffer_size`] function with the desired number of LEDs to choose a correct buffer size, otherwise [`SmartLedsWrite::write`] will return [`AdapterError::BufferSizeExceeded`].
+///   If the LED count is only known at runtime, use a [`SliceRmtSmartLeds`] with a borrowed buffer instead.
 /// - The `Color`.
 ///   This determines the color model and number of channels to be sent.
 /// - The [`ColorOrder`].
@@ -309,17 +300,125 @@ pub mod color_order {
 /// When the driver mode is [`Async`], this type implements the [`SmartLedsWriteAsync`] interface instead.
 /// (You usually don’t need to choose this manually, Rust can deduce it from the passed-in RMT channel.)
 ///
-/// Some common configurations have predefined aliases: [`Ws2812SmartLeds`], [`Sk68xxRgbwSmartLeds`], [`WhiteSmartLeds`], [`Rgb8RmtSmartLeds`].
-pub struct RmtSmartLeds<'d, const BUFFER_SIZE: usize, Mode, C, Order, Timing>
+/// Some common configurations have predefined aliases: [`Ws2812SmartLeds`], [`Ws2812bV5SmartLeds`], [`Ws2813SmartLeds`], [`Ws2814SmartLeds`], [`Ws2815SmartLeds`],
+/// [`Gs8208SmartLeds`], [`Sm16703SmartLeds`], [`Apa106SmartLeds`], [`Ucs1903SmartLeds`], [`Tm1829SmartLeds`], [`Sk68xxRgbwSmartLeds`], [`WhiteSmartLeds`], [`Rgb8RmtSmartLeds`].
+pub type RmtSmartLeds<'d, const BUFFER_SIZE: usize, Mode, C, Order, Timing> =
+    GenericRmtSmartLeds<'d, [PulseCode; BUFFER_SIZE], Mode, C, Order, Timing>;
+
+/// A [`RmtSmartLeds`] that borrows its pulse buffer instead of owning it, so that the LED count can be decided at runtime.
+///
+/// The buffer can come from a `static` or a `StaticCell`, and should be sized with [`buffer_size`] like the `BUFFER_SIZE` of [`RmtSmartLeds`]:
+///
+/// ```rust,ignore
+/// static BUFFER: StaticCell<[PulseCode; buffer_size::<RGB8>(MAX_LEDS)]> = StaticCell::new();
+/// let buffer = BUFFER.init([PulseCode::end_marker(); _]);
+/// let led_count = config.led_count;
+///
+/// let mut led = SliceRmtSmartLeds::<_, RGB8, color_order::Grb, Ws2812Timing>::new_with_buffer(
+///     rmt.channel0,
+///     peripherals.GPIO2,
+///     &mut buffer[..buffer_size::<RGB8>(led_count)],
+/// )?;
+/// ```
+///
+/// All other functionality is the same as with [`RmtSmartLeds`].
+/// Writing more LEDs than the buffer can hold returns [`AdapterError::BufferSizeExceeded`].
+pub type SliceRmtSmartLeds<'d, Mode, C, Order, Timing> =
+    GenericRmtSmartLeds<'d, &'d mut [PulseCode], Mode, C, Order, Timing>;
+
+/// A [`RmtSmartLeds`] whose pulse buffer is allocated on the heap, so that the LED count can be changed at runtime with [`VecRmtSmartLeds::resize`].
+///
+/// ```rust,ignore
+/// let mut led = VecRmtSmartLeds::<_, RGB8, color_order::Grb, Ws2812Timing>::new_with_led_count(
+///     rmt.channel0,
+///     peripherals.GPIO2,
+///     settings.led_count,
+/// )?;
+/// // Later, after the settings changed:
+/// led.resize(settings.led_count);
+/// ```
+///
+/// All other functionality is the same as with [`RmtSmartLeds`].
+#[cfg(feature = "alloc")]
+pub type VecRmtSmartLeds<'d, Mode, C, Order, Timing> =
+    GenericRmtSmartLeds<'d, Vec<PulseCode>, Mode, C, Order, Timing>;
+
+/// An async [`RmtSmartLeds`] with two pulse buffers, so that the next frame can be encoded while the current one is transmitted.
+///
+/// [`prepare`](GenericRmtSmartLeds::prepare) encodes a frame into the back buffer.
+/// [`swap`](GenericRmtSmartLeds::swap) sends the back buffer, and encodes the following frame into the other buffer in the meantime:
+///
+/// ```rust,ignore
+/// let mut led = DoubleBufferedRmtSmartLeds::<{ buffer_size::<RGB8>(300) }, RGB8, color_order::Grb, Ws2812Timing>::new(
+///     rmt.channel0,
+///     peripherals.GPIO2,
+/// )?;
+/// led.prepare(effect.next_frame())?;
+/// loop {
+///     led.swap(effect.next_frame()).await?;
+/// }
+/// ```
+///
+/// The frame colors are produced lazily while encoding, so an iterator that computes the effect also runs during the transmission.
+/// This needs twice the memory of a [`RmtSmartLeds`] for the pulse buffers.
+///
+/// All other functionality is the same as with [`RmtSmartLeds`], and works on the back buffer.
+/// In particular, [`SmartLedsWriteAsync::write`] replaces a prepared frame.
+pub type DoubleBufferedRmtSmartLeds<'d, const BUFFER_SIZE: usize, C, Order, Timing> =
+    GenericRmtSmartLeds<'d, DoubleBuffer<[PulseCode; BUFFER_SIZE]>, Async, C, Order, Timing>;
+
+/// The two pulse buffers of a [`DoubleBufferedRmtSmartLeds`].
+///
+/// Accessing the pulses as a slice accesses the back buffer, which is the one that is sent next.
+pub struct DoubleBuffer<B> {
+    buffers: [B; 2],
+    back: usize,
+}
+
+impl<B> DoubleBuffer<B> {
+    /// Creates a double buffer from two buffers, where `back` is sent first.
+    const fn new(back: B, front: B) -> Self {
+        Self {
+            buffers: [back, front],
+            back: 0,
+        }
+    }
+}
+
+impl<B: AsRef<[PulseCode]>> AsRef<[PulseCode]> for DoubleBuffer<B> {
+    fn as_ref(&self) -> &[PulseCode] {
+        self.buffers[self.back].as_ref()
+    }
+}
+
+impl<B: AsMut<[PulseCode]>> AsMut<[PulseCode]> for DoubleBuffer<B> {
+    fn as_mut(&mut self) -> &mut [PulseCode] {
+        self.buffers[self.back].as_mut()
+    }
+}
+
+/// The driver behind [`RmtSmartLeds`] and [`SliceRmtSmartLeds`], which is generic over the storage of its pulse buffer.
+///
+/// You usually don’t need to name this type directly; see [`RmtSmartLeds`] for documentation.
+pub struct GenericRmtSmartLeds<'d, Buffer, Mode, C, Order, Timing>
 where
+    Buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
     Mode: DriverMode,
     C: Color,
     Order: ColorOrder<C>,
     Timing: crate::Timing,
 {
     channel: Option<Channel<'d, Mode, Tx>>,
-    rmt_buffer: [PulseCode; BUFFER_SIZE],
-    pulses: (PulseCode, PulseCode),
+    rmt_buffer: Buffer,
+    /// Speed of the RMT source clock in MHz, for converting custom pulses.
+    src_clock_mhz: u32,
+    encoder: FrameEncoder,
+    /// Number of LEDs that are switched off by `shutdown`.
+    blanking: Option<usize>,
+    /// Switches the LEDs off when dropping the driver. This is only possible in blocking mode.
+    blank_on_drop: Option<fn(&mut Self)>,
+    /// Called after every completed transmission.
+    on_complete: Option<fn()>,
     _order: PhantomData<Order>,
     _timing: PhantomData<Timing>,
     _color: PhantomData<C>,
@@ -343,32 +442,125 @@ pub type Ws2812SmartLeds<'d, const BUFFER_SIZE: usize, Mode> =
 pub type Sk68xxRgbwSmartLeds<'d, const BUFFER_SIZE: usize, Mode> =
     RmtSmartLeds<'d, BUFFER_SIZE, Mode, RGBW<u8>, color_order::Rgbw, Sk68xxTiming>;
 
+/// A [`RmtSmartLeds`] for the WS2812B “V5” integrated smart LEDs.
+///
+/// You only need to pick the `BUFFER_SIZE` to use this.
+pub type Ws2812bV5SmartLeds<'d, const BUFFER_SIZE: usize, Mode> =
+    Rgb8RmtSmartLeds<'d, BUFFER_SIZE, Mode, color_order::Grb, Ws2812bV5Timing>;
+
+/// A [`RmtSmartLeds`] for the WS2813 integrated smart LEDs.
+///
+/// You only need to pick the `BUFFER_SIZE` to use this.
+pub type Ws2813SmartLeds<'d, const BUFFER_SIZE: usize, Mode> =
+    Rgb8RmtSmartLeds<'d, BUFFER_SIZE, Mode, color_order::Grb, Ws2813Timing>;
+
+/// A [`RmtSmartLeds`] for WS2814 RGBW strips.
+///
+/// You only need to pick the `BUFFER_SIZE` to use this.
+/// Some strip vendors wire the WS2814 channels in a different order, check your strip if the white channel ends up on a color.
+pub type Ws2814SmartLeds<'d, const BUFFER_SIZE: usize, Mode> =
+    RmtSmartLeds<'d, BUFFER_SIZE, Mode, RGBW<u8>, color_order::Rgbw, Ws2814Timing>;
+
+/// A [`RmtSmartLeds`] for the WS2815 integrated 12 V smart LEDs.
+///
+/// You only need to pick the `BUFFER_SIZE` to use this.
+pub type Ws2815SmartLeds<'d, const BUFFER_SIZE: usize, Mode> =
+    Rgb8RmtSmartLeds<'d, BUFFER_SIZE, Mode, color_order::Grb, Ws2815Timing>;
+
+/// A [`RmtSmartLeds`] for the GS8208 integrated 12 V smart LEDs.
+///
+/// You only need to pick the `BUFFER_SIZE` to use this.
+pub type Gs8208SmartLeds<'d, const BUFFER_SIZE: usize, Mode> =
+    Rgb8RmtSmartLeds<'d, BUFFER_SIZE, Mode, color_order::Grb, Gs8208Timing>;
+
+/// A [`RmtSmartLeds`] for the SM16703 integrated smart LEDs.
+///
+/// You only need to pick the `BUFFER_SIZE` to use this.
+pub type Sm16703SmartLeds<'d, const BUFFER_SIZE: usize, Mode> =
+    Rgb8RmtSmartLeds<'d, BUFFER_SIZE, Mode, color_order::Rgb, Sm16703Timing>;
+
+/// A [`RmtSmartLeds`] for the APA106 integrated smart LEDs.
+///
+/// You only need to pick the `BUFFER_SIZE` to use this.
+pub type Apa106SmartLeds<'d, const BUFFER_SIZE: usize, Mode> =
+    Rgb8RmtSmartLeds<'d, BUFFER_SIZE, Mode, color_order::Rgb, Apa106Timing>;
+
+/// A [`RmtSmartLeds`] for RGB strips driven by UCS1903 driver ICs.
+///
+/// You need to pick the `BUFFER_SIZE` and the `Order` to use this, since the channels of discrete driver ICs are wired differently on each strip.
+pub type Ucs1903SmartLeds<'d, const BUFFER_SIZE: usize, Mode, Order> =
+    Rgb8RmtSmartLeds<'d, BUFFER_SIZE, Mode, Order, Ucs1903Timing>;
+
+/// A [`RmtSmartLeds`] for RGB strips driven by TM1829 driver ICs.
+///
+/// You need to pick the `BUFFER_SIZE` and the `Order` to use this, since the channels of discrete driver ICs are wired differently on each strip.
+pub type Tm1829SmartLeds<'d, const BUFFER_SIZE: usize, Mode, Order> =
+    Rgb8RmtSmartLeds<'d, BUFFER_SIZE, Mode, Order, Tm1829Timing>;
+
 /// A [`RmtSmartLeds`] for smart LEDs with a single (white) channel.
 ///
 /// You only need to pick the `BUFFER_SIZE` and `Timing` to use this.
 pub type WhiteSmartLeds<'d, const BUFFER_SIZE: usize, Mode, Timing> =
     RmtSmartLeds<'d, BUFFER_SIZE, Mode, White<u8>, color_order::SingleChannel, Timing>;
 
+/// Converts a time in nanoseconds to RMT ticks, given the RMT source clock’s speed in MHz.
+const fn ns_to_ticks(time_ns: u32, src_clock_mhz: u32) -> u32 {
+    // FIXME: For some reason, we transmit half as many pulses as necessary. This broke somewhere between esp-hal 1.0 and 1.1.
+    //        It’s definitely not the clock reporting’s fault, but that’s all we know.
+    ((time_ns as u64 * src_clock_mhz as u64 * 2) / 1000) as u32
+}
+
+/// Returns the line levels for the (logical) high and low parts of a bit.
+const fn levels<T: Timing>() -> (Level, Level) {
+    if T::INVERTED {
+        (Level::Low, Level::High)
+    } else {
+        (Level::High, Level::Low)
+    }
+}
+
 /// Returns the pulse code for a zero bit, given the RMT source clock’s speed in MHz.
 const fn zero_pulse<T: Timing>(src_clock_mhz: u32) -> PulseCode {
+    let (high, low) = levels::<T>();
     PulseCode::new(
-        Level::High,
-        // FIXME: For some reason, we transmit half as many pulses as necessary. This broke somewhere between esp-hal 1.0 and 1.1.
-        //        It’s definitely not the clock reporting’s fault, but that’s all we know.
-        ((T::TIME_0_HIGH as u32 * src_clock_mhz * 2) / 1000) as u16,
-        Level::Low,
-        ((T::TIME_0_LOW as u32 * src_clock_mhz * 2) / 1000) as u16,
+        high,
+        ns_to_ticks(T::TIME_0_HIGH as u32, src_clock_mhz) as u16,
+        low,
+        ns_to_ticks(T::TIME_0_LOW as u32, src_clock_mhz) as u16,
     )
 }
 /// Returns the pulse code for a one bit, given the RMT source clock’s speed in MHz.
 const fn one_pulse<T: Timing>(src_clock_mhz: u32) -> PulseCode {
+    let (high, low) = levels::<T>();
+    PulseCode::new(
+        high,
+        ns_to_ticks(T::TIME_1_HIGH as u32, src_clock_mhz) as u16,
+        low,
+        ns_to_ticks(T::TIME_1_LOW as u32, src_clock_mhz) as u16,
+    )
+}
+/// Returns the pulse code that holds the line idle for the reset period, given the RMT source clock’s speed in MHz.
+const fn reset_pulse<T: Timing>(src_clock_mhz: u32) -> PulseCode {
+    let (_, idle) = levels::<T>();
+    let ticks = ns_to_ticks(T::TIME_RESET, src_clock_mhz);
+    // Split the period over both halves, each clamped to the longest length a pulse code can represent.
+    let first_half = ticks / 2;
+    let second_half = ticks - first_half;
     PulseCode::new(
-        Level::High,
-        ((T::TIME_1_HIGH as u32 * src_clock_mhz * 2) / 1000) as u16,
-        Level::Low,
-        ((T::TIME_1_LOW as u32 * src_clock_mhz * 2) / 1000) as u16,
+        idle,
+        clamp_ticks(first_half),
+        idle,
+        clamp_ticks(second_half),
     )
 }
+/// Clamps a tick count to the longest length a single half of a pulse code can represent.
+const fn clamp_ticks(ticks: u32) -> u16 {
+    if ticks > PulseCode::MAX_LEN as u32 {
+        PulseCode::MAX_LEN
+    } else {
+        ticks as u16
+    }
+}
 
 impl<'d, const BUFFER_SIZE: usize, Mode, C, Order, Timing>
     RmtSmartLeds<'d, BUFFER_SIZE, Mode, C, Order, Timing>
@@ -412,196 +604,1380 @@ where
         Ch: TxChannelCreator<'d, Mode>,
         P: PeripheralOutput<'d>,
     {
-        let config = TxChannelConfig::default()
-            .with_clk_divider(1)
-            .with_idle_output_level(Level::Low)
-            .with_memsize(memsize)
-            .with_carrier_modulation(false)
-            .with_idle_output(true);
-
-        let channel = channel.configure_tx(&config)?.with_pin(pin);
-
-        // Assume the RMT peripheral is set up to use the APB clock
-        let clocks = Clocks::get();
-        // convert to the MHz value to simplify nanosecond calculations
-        let src_clock = clocks.apb_clock.as_hz() / 1_000_000;
-
-        let mut rmt_buffer = [zero_pulse::<Timing>(src_clock); _];
-        rmt_buffer[BUFFER_SIZE - 1] = PulseCode::end_marker();
-        Ok(Self {
-            channel: Some(channel),
-            rmt_buffer,
-            pulses: (
-                zero_pulse::<Timing>(src_clock),
-                one_pulse::<Timing>(src_clock),
-            ),
-            _order: PhantomData,
-            _timing: PhantomData,
-            _color: PhantomData,
-        })
+        Self::with_buffer(channel, pin, memsize, [PulseCode::end_marker(); _])
     }
 
-    /// Create and store RMT data from the color information provided.
-    fn create_rmt_data(
-        &mut self,
-        iterator: impl IntoIterator<Item = impl Into<C>>,
-    ) -> Result<(), AdapterError> {
-        // We always start from the beginning of the buffer
-        let mut seq_iter = self.rmt_buffer.iter_mut();
-
-        // Add all converted iterator items to the buffer.
-        // This will result in an `BufferSizeExceeded` error in case
-        // the iterator provides more elements than the buffer can take.
-        for item in iterator {
-            convert_colors_to_pulse::<_, Order>(&item.into(), &mut seq_iter, self.pulses)?;
-        }
-
-        // Finally, add an end element.
-        *seq_iter.next().ok_or(AdapterError::BufferSizeExceeded)? = PulseCode::end_marker();
-
-        Ok(())
-    }
+    /// Number of LEDs that fit into the buffer, like [`GenericRmtSmartLeds::capacity`].
+    pub const LED_CAPACITY: usize =
+        BUFFER_SIZE.saturating_sub(2) / (C::CHANNELS as usize * (size_of::<C::ChannelType>() * 8));
+}
 
-    /// Write pixel buffer data at certain LED index.
-    /// Does not actually write data to the RMT peripheral.
-    #[allow(unused)]
-    pub(crate) fn write_pixel_data(
-        &mut self,
-        index: usize,
-        color: impl Into<C>,
-    ) -> Result<(), AdapterError> {
-        let buffer_start_index = index * C::CHANNELS as usize * (size_of::<C::ChannelType>() * 8);
-        let mut buffer_iter = self
-            .rmt_buffer
-            .get_mut(buffer_start_index..)
-            .ok_or(AdapterError::BufferSizeExceeded)?
-            .iter_mut();
-        convert_colors_to_pulse::<_, Order>(&color.into(), &mut buffer_iter, self.pulses)
+impl<'d, const BUFFER_SIZE: usize, C, Order, Timing>
+    RmtSmartLeds<'d, BUFFER_SIZE, Blocking, C, Order, Timing>
+where
+    C: Color,
+    Order: ColorOrder<C>,
+    Timing: crate::Timing,
+{
+    /// Writes an array of colors, checking at compile time that they fit into the buffer.
+    ///
+    /// Writing more than [`Self::LED_CAPACITY`] colors fails to build, instead of returning [`AdapterError::BufferSizeExceeded`] at runtime.
+    pub fn write_array<I, const N: usize>(&mut self, colors: [I; N]) -> Result<(), AdapterError>
+    where
+        I: Into<C>,
+    {
+        const {
+            assert!(
+                N <= Self::LED_CAPACITY,
+                "more colors than the LED buffer can hold"
+            )
+        };
+        self.write(colors)
     }
 }
 
 impl<'d, const BUFFER_SIZE: usize, C, Order, Timing>
-    RmtSmartLeds<'d, BUFFER_SIZE, Blocking, C, Order, Timing>
+    RmtSmartLeds<'d, BUFFER_SIZE, Async, C, Order, Timing>
 where
     C: Color,
     Order: ColorOrder<C>,
     Timing: crate::Timing,
 {
-    /// Transmit existing LED data via the RMT peripheral.
-    pub fn flush(&mut self) -> Result<(), AdapterError> {
-        // Perform the actual RMT operation. We use the u32 values here right away.
-        let channel = self.channel.take().unwrap();
-        // TODO: If the transmit fails, we’re in an unsafe state and future calls to write() will panic.
-        // This is currently unavoidable since transmit consumes the channel on error.
-        // This is a known design flaw in the current RMT API and will be fixed soon.
-        // We should adjust our usage accordingly as soon as possible.
-        match channel
-            .transmit(&self.rmt_buffer)
-            .map_err(|(e, _)| e)?
-            .wait()
-        {
-            Ok(chan) => {
-                self.channel = Some(chan);
-                Ok(())
-            }
-            Err((e, chan)) => {
-                self.channel = Some(chan);
-                Err(AdapterError::TransmissionError(e))
-            }
-        }
+    /// Writes an array of colors, checking at compile time that they fit into the buffer.
+    ///
+    /// Writing more than [`Self::LED_CAPACITY`] colors fails to build, instead of returning [`AdapterError::BufferSizeExceeded`] at runtime.
+    pub fn write_array<I, const N: usize>(
+        &mut self,
+        colors: [I; N],
+    ) -> impl Future<Output = Result<(), AdapterError>>
+    where
+        I: Into<C>,
+    {
+        const {
+            assert!(
+                N <= Self::LED_CAPACITY,
+                "more colors than the LED buffer can hold"
+            )
+        };
+        self.write(colors)
     }
 }
 
-impl<'d, const BUFFER_SIZE: usize, C, Order, Timing> SmartLedsWrite
-    for RmtSmartLeds<'d, BUFFER_SIZE, Blocking, C, Order, Timing>
+impl<'d, Mode, C, Order, Timing> SliceRmtSmartLeds<'d, Mode, C, Order, Timing>
 where
+    Mode: DriverMode,
     C: Color,
     Order: ColorOrder<C>,
     Timing: crate::Timing,
 {
-    type Error = AdapterError;
-    type Color = C;
+    /// Creates a new [`SliceRmtSmartLeds`] that drives the provided output using the given RMT channel and pulse buffer.
+    ///
+    /// The buffer should be sized with [`buffer_size`] for the desired number of LEDs. Its previous contents don’t matter.
+    ///
+    /// # Errors
+    ///
+    /// If any configuration issue with the RMT [`Channel`] occurs, the error will be returned.
+    pub fn new_with_buffer<Ch, P>(
+        channel: Ch,
+        pin: P,
+        buffer: &'d mut [PulseCode],
+    ) -> Result<Self, RmtConfigError>
+    where
+        Ch: TxChannelCreator<'d, Mode>,
+        P: PeripheralOutput<'d>,
+    {
+        Self::with_buffer(channel, pin, 1, buffer)
+    }
 
-    /// Convert all Color items of the iterator to the RMT format and
-    /// add them to internal buffer, then start a singular RMT operation
-    /// based on that buffer.
-    fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    /// Creates a new [`SliceRmtSmartLeds`] that drives the provided output using the given RMT channel and pulse buffer,
+    /// using `memsize` RMT memory blocks.
+    ///
+    /// See [`RmtSmartLeds::new_with_memsize`] for details on the `memsize` parameter.
+    ///
+    /// # Errors
+    ///
+    /// If any configuration issue with the RMT [`Channel`] occurs, the error will be returned.
+    pub fn new_with_buffer_and_memsize<Ch, P>(
+        channel: Ch,
+        pin: P,
+        buffer: &'d mut [PulseCode],
+        memsize: u8,
+    ) -> Result<Self, RmtConfigError>
     where
-        T: IntoIterator<Item = I>,
-        I: Into<Self::Color>,
+        Ch: TxChannelCreator<'d, Mode>,
+        P: PeripheralOutput<'d>,
     {
-        self.create_rmt_data(iterator)?;
-        self.flush()
+        Self::with_buffer(channel, pin, memsize, buffer)
     }
 }
 
-impl<'d, const BUFFER_SIZE: usize, C, Order, Timing> SmartLedsWriteAsync
-    for RmtSmartLeds<'d, BUFFER_SIZE, Async, C, Order, Timing>
+#[cfg(feature = "alloc")]
+impl<'d, Mode, C, Order, Timing> VecRmtSmartLeds<'d, Mode, C, Order, Timing>
 where
+    Mode: DriverMode,
     C: Color,
     Order: ColorOrder<C>,
     Timing: crate::Timing,
 {
-    type Error = AdapterError;
-    type Color = C;
+    /// Creates a new [`VecRmtSmartLeds`] that drives the provided output using the given RMT channel,
+    /// with a buffer for `led_count` LEDs.
+    ///
+    /// # Errors
+    ///
+    /// If any configuration issue with the RMT [`Channel`] occurs, the error will be returned.
+    pub fn new_with_led_count<Ch, P>(
+        channel: Ch,
+        pin: P,
+        led_count: usize,
+    ) -> Result<Self, RmtConfigError>
+    where
+        Ch: TxChannelCreator<'d, Mode>,
+        P: PeripheralOutput<'d>,
+    {
+        Self::new_with_led_count_and_memsize(channel, pin, led_count, 1)
+    }
 
-    /// Convert all Color items of the iterator to the RMT format and
-    /// add them to internal buffer, then start a singular RMT operation
-    /// based on that buffer.
-    fn write<T, I>(&mut self, iterator: T) -> impl Future<Output = Result<(), Self::Error>>
+    /// Creates a new [`VecRmtSmartLeds`] that drives the provided output using the given RMT channel,
+    /// with a buffer for `led_count` LEDs and using `memsize` RMT memory blocks.
+    ///
+    /// See [`RmtSmartLeds::new_with_memsize`] for details on the `memsize` parameter.
+    ///
+    /// # Errors
+    ///
+    /// If any configuration issue with the RMT [`Channel`] occurs, the error will be returned.
+    pub fn new_with_led_count_and_memsize<Ch, P>(
+        channel: Ch,
+        pin: P,
+        led_count: usize,
+        memsize: u8,
+    ) -> Result<Self, RmtConfigError>
     where
-        T: IntoIterator<Item = I>,
-        I: Into<Self::Color>,
+        Ch: TxChannelCreator<'d, Mode>,
+        P: PeripheralOutput<'d>,
     {
-        // we split the future into a creation part and a sending part
-        // so we can prepare multiple futures and send/await then all at the same time
-        let res = self.create_rmt_data(iterator);
+        let buffer = vec![PulseCode::end_marker(); buffer_size::<C>(led_count)];
+        Self::with_buffer(channel, pin, memsize, buffer)
+    }
 
-        async move {
-            res?;
-            // Perform the actual RMT operation. We use the u32 values here right away.
-            self.channel
-                .as_mut()
-                .unwrap()
-                .transmit(&self.rmt_buffer)
-                .await?;
-            Ok(())
+    /// Changes the number of LEDs that the buffer can hold.
+    ///
+    /// This clears the buffer, so [`Self::flush`] sends an all-black frame until the next write.
+    /// LEDs beyond the new count are not written anymore, so they keep their last color; use [`Self::shutdown`] beforehand to switch them off.
+    /// If the buffer shrinks below the number of LEDs configured for [blanking](Self::set_blanking), the blanking is reduced to the new count.
+    pub fn resize(&mut self, led_count: usize) {
+        self.rmt_buffer.clear();
+        self.rmt_buffer
+            .resize(buffer_size::<C>(led_count), self.encoder.lut.zero());
+        if let [.., reset, end] = self.rmt_buffer.as_mut_slice() {
+            *reset = self.encoder.reset_pulse;
+            *end = PulseCode::end_marker();
         }
+        self.blanking = self.blanking.map(|count| count.min(led_count));
     }
 }
 
-fn convert_colors_to_pulse<'a, C, Order>(
-    value: &C,
-    mut_iter: &mut impl Iterator<Item = &'a mut PulseCode>,
-    pulses: (PulseCode, PulseCode),
-) -> Result<(), AdapterError>
+impl<'d, const BUFFER_SIZE: usize, C, Order, Timing>
+    DoubleBufferedRmtSmartLeds<'d, BUFFER_SIZE, C, Order, Timing>
 where
     C: Color,
     Order: ColorOrder<C>,
+    Timing: crate::Timing,
 {
-    for channel in 0..C::CHANNELS {
-        convert_channel_to_pulses(Order::get_channel_data(&value, channel), mut_iter, pulses)?;
+    /// Creates a new [`DoubleBufferedRmtSmartLeds`] that drives the provided output using the given RMT channel.
+    ///
+    /// This works like [`RmtSmartLeds::new`], but allocates two pulse buffers of `BUFFER_SIZE`.
+    ///
+    /// # Errors
+    ///
+    /// If any configuration issue with the RMT [`Channel`] occurs, the error will be returned.
+    pub fn new<Ch, P>(channel: Ch, pin: P) -> Result<Self, RmtConfigError>
+    where
+        Ch: TxChannelCreator<'d, Async>,
+        P: PeripheralOutput<'d>,
+    {
+        Self::new_with_memsize(channel, pin, 1)
     }
 
-    Ok(())
+    /// Creates a new [`DoubleBufferedRmtSmartLeds`] that drives the provided output using the given RMT channel and number of RMT memory blocks.
+    ///
+    /// This works like [`RmtSmartLeds::new_with_memsize`], but allocates two pulse buffers of `BUFFER_SIZE`.
+    ///
+    /// # Errors
+    ///
+    /// If any configuration issue with the RMT [`Channel`] occurs, the error will be returned.
+    pub fn new_with_memsize<Ch, P>(channel: Ch, pin: P, memsize: u8) -> Result<Self, RmtConfigError>
+    where
+        Ch: TxChannelCreator<'d, Async>,
+        P: PeripheralOutput<'d>,
+    {
+        let buffers = DoubleBuffer::new(
+            [PulseCode::end_marker(); BUFFER_SIZE],
+            [PulseCode::end_marker(); BUFFER_SIZE],
+        );
+        Self::with_buffer(channel, pin, memsize, buffers)
+    }
 }
 
-fn convert_channel_to_pulses<'a, N>(
-    channel_value: N,
-    mut_iter: &mut impl Iterator<Item = &'a mut PulseCode>,
-    pulses: (PulseCode, PulseCode),
-) -> Result<(), AdapterError>
+impl<'d, Buffer, Mode, C, Order, Timing> GenericRmtSmartLeds<'d, Buffer, Mode, C, Order, Timing>
 where
-    N: Unsigned + Into<usize>,
+    Buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+    Mode: DriverMode,
+    C: Color,
+    Order: ColorOrder<C>,
+    Timing: crate::Timing,
 {
-    let channel_value: usize = channel_value.into();
-    for index in (0..size_of::<N>() * 8).rev() {
-        let position = 1 << index;
-        *mut_iter.next().ok_or(AdapterError::BufferSizeExceeded)? = match channel_value & position {
-            0 => pulses.0,
-            _ => pulses.1,
-        }
+    /// Configures the RMT channel and prepares the buffer, so that flushing it sends a reset period.
+    fn with_buffer<Ch, P>(
+        channel: Ch,
+        pin: P,
+        memsize: u8,
+        mut rmt_buffer: Buffer,
+    ) -> Result<Self, RmtConfigError>
+    where
+        Ch: TxChannelCreator<'d, Mode>,
+        P: PeripheralOutput<'d>,
+    {
+        let config = TxChannelConfig::default()
+            .with_clk_divider(1)
+            .with_idle_output_level(levels::<Timing>().1)
+            .with_memsize(memsize)
+            .with_carrier_modulation(false)
+            .with_idle_output(true);
+
+        let channel = channel.configure_tx(&config)?.with_pin(pin);
+
+        // Assume the RMT peripheral is set up to use the APB clock
+        let clocks = Clocks::get();
+        // convert to the MHz value to simplify nanosecond calculations
+        let src_clock = clocks.apb_clock.as_hz() / 1_000_000;
+
+        let pulses = rmt_buffer.as_mut();
+        if let [data @ .., reset, end] = pulses {
+            data.fill(zero_pulse::<Timing>(src_clock));
+            *reset = reset_pulse::<Timing>(src_clock);
+            *end = PulseCode::end_marker();
+        }
+        Ok(Self {
+            channel: Some(channel),
+            rmt_buffer,
+            src_clock_mhz: src_clock,
+            encoder: FrameEncoder {
+                lut: PulseLut::new(
+                    zero_pulse::<Timing>(src_clock),
+                    one_pulse::<Timing>(src_clock),
+                ),
+                reset_pulse: reset_pulse::<Timing>(src_clock),
+                correction: ColorCorrection::new(),
+                power: PowerLimiter::new(Timing::POWER),
+                overflow: Overflow::Error,
+                underflow: Underflow::Leave,
+                last_write: WriteReport::default(),
+            },
+            blanking: None,
+            blank_on_drop: None,
+            on_complete: None,
+            _order: PhantomData,
+            _timing: PhantomData,
+            _color: PhantomData,
+        })
+    }
+
+    /// Returns the number of LEDs that fit into the pulse buffer.
+    pub fn capacity(&self) -> usize {
+        let led_pulses = C::CHANNELS as usize * (size_of::<C::ChannelType>() * 8);
+        self.rmt_buffer.as_ref().len().saturating_sub(2) / led_pulses
+    }
+
+    /// Sets the transfer function that maps color values to linear LED output, or disables it with `None`.
+    ///
+    /// This replaces gamma correction in user code (like [`smart_leds::gamma`](https://docs.rs/smart-leds/latest/smart_leds/fn.gamma.html)), and works for any channel bit width.
+    /// See the [`correction`] module for details.
+    pub fn set_gamma(&mut self, gamma: Option<&'static GammaLut>) {
+        self.encoder.correction.gamma = gamma;
+    }
+
+    /// Returns the transfer function set with [`Self::set_gamma`].
+    pub fn gamma(&self) -> Option<&'static GammaLut> {
+        self.encoder.correction.gamma
+    }
+
+    /// Sets the global brightness, where 255 is full brightness.
+    ///
+    /// Brightness is applied in linear space after the transfer function, so it scales the emitted light proportionally.
+    pub fn set_brightness(&mut self, brightness: u8) {
+        self.encoder.correction.brightness = brightness;
+    }
+
+    /// Returns the global brightness set with [`Self::set_brightness`].
+    pub fn brightness(&self) -> u8 {
+        self.encoder.correction.brightness
+    }
+
+    /// Sets the per-channel white balance, which scales each channel to compensate for the tint of the LEDs.
+    ///
+    /// Use [`WhiteBalance::UNCORRECTED`] to disable white balancing.
+    pub fn set_white_balance(&mut self, white_balance: WhiteBalance) {
+        self.encoder.correction.white_balance = white_balance;
+    }
+
+    /// Returns the white balance set with [`Self::set_white_balance`].
+    pub fn white_balance(&self) -> WhiteBalance {
+        self.encoder.correction.white_balance
+    }
+
+    /// Sets a color correction matrix that is applied to every color, or disables it with `None`.
+    pub fn set_correction_matrix(&mut self, matrix: Option<CorrectionMatrix>) {
+        self.encoder.correction.matrix = matrix;
+    }
+
+    /// Returns the color correction matrix set with [`Self::set_correction_matrix`].
+    pub fn correction_matrix(&self) -> Option<CorrectionMatrix> {
+        self.encoder.correction.matrix
+    }
+
+    /// Enables or disables temporal dithering.
+    ///
+    /// With dithering, every write rounds the corrected colors differently, so that their average over several frames is more precise than the LEDs’ channel type.
+    /// This needs a steady refresh loop to work well; see the [`correction`] module for details.
+    /// Note that [`Self::flush`] sends the same frame again and doesn’t advance the dithering.
+    pub fn set_dithering(&mut self, dithering: bool) {
+        self.encoder.correction.dithering = dithering;
+    }
+
+    /// Returns whether temporal dithering is enabled.
+    pub fn dithering(&self) -> bool {
+        self.encoder.correction.dithering
+    }
+
+    /// Sets a per-pixel calibration table, or disables calibration with `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CalibrationError::ChannelMismatch`] if the table’s channel count doesn’t match the color type.
+    pub fn set_calibration(
+        &mut self,
+        calibration: Option<Calibration>,
+    ) -> Result<(), CalibrationError> {
+        if calibration.is_some_and(|calibration| calibration.channels() != C::CHANNELS) {
+            return Err(CalibrationError::ChannelMismatch);
+        }
+        self.encoder.correction.calibration = calibration;
+        Ok(())
+    }
+
+    /// Returns the calibration table set with [`Self::set_calibration`].
+    pub fn calibration(&self) -> Option<Calibration> {
+        self.encoder.correction.calibration
+    }
+
+    /// Sets the maximum current that the LEDs may draw, in milliamps, or disables power limiting with `None`.
+    ///
+    /// Frames whose estimated current draw exceeds the budget are scaled down uniformly.
+    /// Only the LEDs that are written are taken into account. See the [`power`] module for details.
+    pub fn set_power_budget(&mut self, milliamps: Option<u32>) {
+        self.encoder.power.budget_milliamps = milliamps;
+    }
+
+    /// Returns the power budget set with [`Self::set_power_budget`].
+    pub fn power_budget(&self) -> Option<u32> {
+        self.encoder.power.budget_milliamps
+    }
+
+    /// Sets the model used to estimate the current draw of the LEDs.
+    ///
+    /// The default is the [`Timing::POWER`] of the LED type.
+    pub fn set_power_model(&mut self, model: PowerModel) {
+        self.encoder.power.model = model;
+    }
+
+    /// Returns the power model set with [`Self::set_power_model`].
+    pub fn power_model(&self) -> PowerModel {
+        self.encoder.power.model
+    }
+
+    /// Sets a budget for the average current draw over time, or disables thermal limiting with `None`.
+    ///
+    /// This restarts the moving average. See the [`power`] module for details.
+    pub fn set_thermal_limit(&mut self, limit: Option<ThermalLimit>) {
+        self.encoder.power.set_thermal_limit(limit);
+    }
+
+    /// Returns the thermal limit set with [`Self::set_thermal_limit`].
+    pub fn thermal_limit(&self) -> Option<ThermalLimit> {
+        self.encoder.power.thermal_limit
+    }
+
+    /// Sets the timestamp of the next written frame for the thermal limiter.
+    ///
+    /// Without this, the thermal limiter uses [`Instant::now`] at the time of writing.
+    /// The timestamp only applies to the next frame, so it needs to be set before every write.
+    pub fn set_frame_time(&mut self, time: Instant) {
+        self.encoder.power.frame_time = Some(time);
+    }
+
+    /// Enables a soft start that ramps the brightness up over the given period, or disables it with `None`.
+    ///
+    /// The ramp starts with the next written frame. See the [`power`] module for details.
+    pub fn set_soft_start(&mut self, period: Option<Duration>) {
+        self.encoder.power.set_soft_start(period);
+    }
+
+    /// Returns the soft start period set with [`Self::set_soft_start`].
+    pub fn soft_start(&self) -> Option<Duration> {
+        self.encoder.power.soft_start
+    }
+
+    /// Restarts the soft start ramp with the next written frame, for example after the LEDs’ power supply was switched on.
+    ///
+    /// This has no effect if soft start is disabled.
+    pub fn restart_soft_start(&mut self) {
+        self.encoder.power.restart_soft_start();
+    }
+
+    /// Returns the estimated current draw and the applied power limiting of the last written frame.
+    pub fn power_report(&self) -> PowerReport {
+        self.encoder.power.report
+    }
+
+    /// Sets what happens when a write provides more colors than the buffer can hold. The default is [`Overflow::Error`].
+    pub fn set_overflow(&mut self, overflow: Overflow) {
+        self.encoder.overflow = overflow;
+    }
+
+    /// Returns the overflow policy set with [`Self::set_overflow`].
+    pub fn overflow(&self) -> Overflow {
+        self.encoder.overflow
+    }
+
+    /// Sets what happens when a write provides fewer colors than the buffer can hold. The default is [`Underflow::Leave`].
+    pub fn set_underflow(&mut self, underflow: Underflow) {
+        self.encoder.underflow = underflow;
+    }
+
+    /// Returns the underflow policy set with [`Self::set_underflow`].
+    pub fn underflow(&self) -> Underflow {
+        self.encoder.underflow
+    }
+
+    /// Returns how many LEDs the last write encoded, filled and dropped.
+    pub fn last_write(&self) -> WriteReport {
+        self.encoder.last_write
+    }
+
+    /// Returns the number of LEDs that are switched off on shutdown, as configured with `with_blanking` or `set_blanking`.
+    pub fn blanking(&self) -> Option<usize> {
+        self.blanking
+    }
+
+    /// Encodes colors into the buffer, without sending them.
+    ///
+    /// The prepared frame is sent by [`flush`](GenericRmtSmartLeds::flush) in blocking mode, by [`swap`](GenericRmtSmartLeds::swap) of a [`DoubleBufferedRmtSmartLeds`],
+    /// or along with other strips by a [`StripGroup`](group::StripGroup).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdapterError::BufferSizeExceeded`] if the buffer can’t hold the colors (depending on the [`Overflow`] policy).
+    pub fn prepare<T, I>(&mut self, iterator: T) -> Result<(), AdapterError>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<C>,
+    {
+        self.create_rmt_data(iterator)
+    }
+
+    /// Sets a function that is called after every completed transmission, or removes it with `None`.
+    ///
+    /// Since every frame ends with the reset period of the [`Timing`], the LEDs have latched the frame when the function is called.
+    /// (Frames of custom pulses only include a reset period if the pulses do.)
+    /// Use this to synchronize other events with the LEDs, like starting audio playback.
+    ///
+    /// The driver only owns its channel, not the RMT peripheral whose interrupt handler is set by `Rmt::set_interrupt_handler`,
+    /// so the function is not called from the interrupt handler. Instead, the driver calls it as soon as it notices the completion:
+    /// when a blocking transmission is finished or first [polled](Transmission::poll) as done, or when an async transmission wakes up its task.
+    /// Keep the function short, since it delays the rest of the write.
+    pub fn set_on_complete(&mut self, on_complete: Option<fn()>) {
+        self.on_complete = on_complete;
+    }
+
+    /// Returns the function called after every completed transmission.
+    pub fn on_complete(&self) -> Option<fn()> {
+        self.on_complete
+    }
+
+    /// Creates a pulse code from two line levels with durations in nanoseconds, converted with the driver’s RMT clock.
+    ///
+    /// Use this to build pulses for [`write_pulses`](Self::write_pulses) and [`write_with_preamble`](Self::write_with_preamble),
+    /// like configuration headers or address programming sequences.
+    /// Each duration is clamped to the range a pulse code can represent, so that a short duration never ends the transmission early.
+    /// Longer periods need to be split over several pulse codes.
+    pub fn pulse(
+        &self,
+        level1: Level,
+        length1_ns: u32,
+        level2: Level,
+        length2_ns: u32,
+    ) -> PulseCode {
+        let ticks = |ns| clamp_ticks(ns_to_ticks(ns, self.src_clock_mhz)).max(1);
+        PulseCode::new(level1, ticks(length1_ns), level2, ticks(length2_ns))
+    }
+
+    /// Store an all-black frame for the configured number of LEDs, bypassing all corrections.
+    ///
+    /// The frame is limited to the capacity of the buffer, so that a buffer that shrank since blanking was configured still switches off all LEDs it can reach.
+    fn create_blank_frame(&mut self) -> Result<(), AdapterError> {
+        self.encoder
+            .lut
+            .encode_blank(
+                self.blanking.unwrap_or(0),
+                C::CHANNELS as usize * (size_of::<C::ChannelType>() * 8),
+                self.encoder.reset_pulse,
+                PulseCode::end_marker(),
+                self.rmt_buffer.as_mut(),
+            )
+            .ok_or(AdapterError::BufferSizeExceeded)?;
+        Ok(())
+    }
+
+    /// Pads the stored frame with black LEDs to at least `led_count` LEDs, and reverses the order of its LEDs.
+    fn reverse_frame(&mut self, led_count: usize) -> Result<(), AdapterError> {
+        let led_pulses = C::CHANNELS as usize * (size_of::<C::ChannelType>() * 8);
+        let frame_leds = self.encoder.last_write.encoded + self.encoder.last_write.filled;
+        let end = frame_leds.max(led_count) * led_pulses;
+        let frame = self
+            .rmt_buffer
+            .as_mut()
+            .get_mut(..end + 2)
+            .ok_or(AdapterError::BufferSizeExceeded)?;
+        frame[frame_leds * led_pulses..end].fill(self.encoder.lut.zero());
+        frame[end] = self.encoder.reset_pulse;
+        frame[end + 1] = PulseCode::end_marker();
+        // Reversing all pulses also reverses the pulses within each LED, which are restored afterwards.
+        frame[..end].reverse();
+        for led in frame[..end].chunks_exact_mut(led_pulses) {
+            led.reverse();
+        }
+        Ok(())
+    }
+
+    /// Create and store RMT data from the color information provided.
+    fn create_rmt_data(
+        &mut self,
+        iterator: impl IntoIterator<Item = impl Into<C>>,
+    ) -> Result<(), AdapterError> {
+        self.create_rmt_data_with_preamble(&[], iterator)
+    }
+
+    /// Create and store RMT data from the color information provided, after custom pulses.
+    fn create_rmt_data_with_preamble(
+        &mut self,
+        preamble: &[PulseCode],
+        iterator: impl IntoIterator<Item = impl Into<C>>,
+    ) -> Result<(), AdapterError> {
+        let reset = self.encoder.reset_pulse;
+        encode_with_preamble(
+            self.rmt_buffer.as_mut(),
+            preamble,
+            reset,
+            PulseCode::end_marker(),
+            AdapterError::BufferSizeExceeded,
+            |buffer| {
+                self.encoder.encode_frame::<C, Order, _>(
+                    buffer,
+                    iterator,
+                    |correction, item, index| {
+                        let mut color = item.into();
+                        correction.apply(&mut color, index);
+                        color
+                    },
+                )
+            },
+        )
+    }
+
+    /// Create and store RMT data from color information with a higher precision than the LEDs support.
+    fn create_rmt_data_high_precision<W>(
+        &mut self,
+        iterator: impl IntoIterator<Item = impl Into<W>>,
+    ) -> Result<(), AdapterError>
+    where
+        W: Color,
+        C: Default,
+    {
+        const {
+            assert!(
+                W::CHANNELS == C::CHANNELS,
+                "high-precision color must have the same channels as the LED color"
+            )
+        };
+        let buffer = &mut self.rmt_buffer.as_mut()[0..];
+        self.encoder
+            .encode_frame::<C, Order, _>(buffer, iterator, |correction, item, index| {
+                let mut color = C::default();
+                correction.apply_to(&item.into(), &mut color, index);
+                color
+            })
+    }
+
+    /// Store RMT data for raw bytes in wire order, without any conversion.
+    fn create_rmt_data_raw(&mut self, bytes: &[u8]) -> Result<(), AdapterError> {
+        let led_bytes = C::CHANNELS as usize * size_of::<C::ChannelType>();
+        if !bytes.len().is_multiple_of(led_bytes) {
+            return Err(AdapterError::InvalidDataLength);
+        }
+        let led_count = bytes.len() / led_bytes;
+        if led_count > self.capacity() {
+            return Err(AdapterError::BufferSizeExceeded);
+        }
+
+        let buffer = self.rmt_buffer.as_mut();
+        // Wider channels are sent most significant byte first, so the bytes can be encoded one after another regardless of the channel type.
+        let (byte_pulses, _) = buffer.as_chunks_mut::<8>();
+        for (byte, out) in bytes.iter().zip(byte_pulses) {
+            self.encoder.lut.encode(*byte as usize, out);
+        }
+        let end = bytes.len() * 8;
+        buffer[end] = self.encoder.reset_pulse;
+        buffer[end + 1] = PulseCode::end_marker();
+
+        self.encoder.last_write = WriteReport {
+            encoded: led_count,
+            filled: 0,
+            dropped: 0,
+        };
+        Ok(())
+    }
+
+    /// Store custom pulses instead of LED data.
+    fn create_rmt_data_pulses(&mut self, pulses: &[PulseCode]) -> Result<(), AdapterError> {
+        let (end, data) = self
+            .rmt_buffer
+            .as_mut()
+            .get_mut(..pulses.len() + 1)
+            .ok_or(AdapterError::BufferSizeExceeded)?
+            .split_last_mut()
+            .unwrap();
+        data.copy_from_slice(pulses);
+        *end = PulseCode::end_marker();
+        self.encoder.last_write = WriteReport::default();
+        Ok(())
+    }
+
+    /// Write pixel buffer data at certain LED index.
+    /// Does not actually write data to the RMT peripheral.
+    #[allow(unused)]
+    pub(crate) fn write_pixel_data(
+        &mut self,
+        index: usize,
+        color: impl Into<C>,
+    ) -> Result<(), AdapterError> {
+        let led_pulses = C::CHANNELS as usize * (size_of::<C::ChannelType>() * 8);
+        let buffer_start_index = index * led_pulses;
+        let out = self
+            .rmt_buffer
+            .as_mut()
+            .get_mut(buffer_start_index..buffer_start_index + led_pulses)
+            .ok_or(AdapterError::BufferSizeExceeded)?;
+        let mut color = color.into();
+        self.encoder.correction.apply(&mut color, index);
+        convert_colors_to_pulse::<_, Order>(&color, out, &self.encoder.lut);
+        Ok(())
+    }
+}
+
+impl<'d, Buffer, C, Order, Timing> GenericRmtSmartLeds<'d, Buffer, Blocking, C, Order, Timing>
+where
+    Buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+    C: Color,
+    Order: ColorOrder<C>,
+    Timing: crate::Timing,
+{
+    /// Write colors with a higher precision than the LEDs support, like `RGB<u16>` for 8-bit LEDs.
+    ///
+    /// The colors are corrected at their full precision, then rounded to the LEDs’ channel type (with dithering, if enabled).
+    /// `W` must have the same channels as the LED color type, in the same natural order.
+    pub fn write_high_precision<T, I, W>(&mut self, iterator: T) -> Result<(), AdapterError>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<W>,
+        W: Color,
+        C: Default,
+    {
+        self.create_rmt_data_high_precision(iterator)?;
+        self.flush()
+    }
+
+    /// Switches off the first `led_count` LEDs right away, and again on [`Self::shutdown`] and when the driver is dropped.
+    ///
+    /// Use this right after construction to clear whatever the LEDs latched before a reset:
+    ///
+    /// ```rust,ignore
+    /// let mut led = Ws2812SmartLeds::<{ buffer_size::<RGB8>(60) }, _>::new(rmt.channel0, peripherals.GPIO2)?
+    ///     .with_blanking(60)
+    ///     .map_err(|(error, _)| error)?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdapterError::BufferSizeExceeded`] if the buffer can’t hold `led_count` LEDs, or an error of the transmission.
+    /// The driver is returned along with the error, with blanking configured unless the LEDs didn’t fit.
+    // The driver is large, but it is moved by value on success as well.
+    #[allow(clippy::result_large_err)]
+    pub fn with_blanking(mut self, led_count: usize) -> Result<Self, (AdapterError, Self)> {
+        if let Err(error) = self.set_blanking(Some(led_count)) {
+            return Err((error, self));
+        }
+        match self.shutdown() {
+            Ok(()) => Ok(self),
+            Err(error) => Err((error, self)),
+        }
+    }
+
+    /// Sets the number of LEDs that are switched off on [`Self::shutdown`] and when the driver is dropped, or disables blanking with `None`.
+    ///
+    /// Unlike [`Self::with_blanking`], this doesn’t switch the LEDs off right away.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdapterError::BufferSizeExceeded`] if the buffer can’t hold `led_count` LEDs. The previous setting is kept in that case.
+    pub fn set_blanking(&mut self, led_count: Option<usize>) -> Result<(), AdapterError> {
+        if led_count.is_some_and(|count| count > self.capacity()) {
+            return Err(AdapterError::BufferSizeExceeded);
+        }
+        self.blanking = led_count;
+        self.blank_on_drop = match led_count {
+            Some(_) => Some(|driver| {
+                // Errors can’t be reported while dropping, and a failed transmission already left the channel unusable.
+                if driver.channel.is_some() {
+                    let _ = driver.shutdown();
+                }
+            }),
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Switches off the LEDs configured with [`Self::with_blanking`] or [`Self::set_blanking`], or does nothing if blanking is not configured.
+    ///
+    /// Afterwards, the RMT channel holds the data line at the idle level of the [`Timing`] until the next write.
+    pub fn shutdown(&mut self) -> Result<(), AdapterError> {
+        if self.blanking.is_none() {
+            return Ok(());
+        }
+        self.create_blank_frame()?;
+        self.flush()
+    }
+
+    /// Write raw bytes in the order they are sent to the LEDs, like GRB bytes for WS2812.
+    ///
+    /// The bytes are encoded into pulses directly, without any conversion through the color type or [`ColorOrder`].
+    /// Color corrections, power and thermal limiting, soft start, and the overflow and underflow policies are not applied.
+    /// For color types with wider channels, every channel consists of several bytes, most significant byte first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdapterError::InvalidDataLength`] if the bytes don’t make up whole LEDs,
+    /// and [`AdapterError::BufferSizeExceeded`] if the buffer can’t hold them.
+    pub fn write_raw_bytes(&mut self, bytes: &[u8]) -> Result<(), AdapterError> {
+        self.create_rmt_data_raw(bytes)?;
+        self.flush()
+    }
+
+    /// Transmit custom pulses instead of LED data, like an address programming sequence.
+    ///
+    /// The pulses are sent as they are, followed by nothing but an end marker, so include a reset period if the protocol needs one.
+    /// Create pulses with durations in nanoseconds with [`Self::pulse`].
+    /// A pulse code with a zero length ends the transmission early.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdapterError::BufferSizeExceeded`] if the buffer can’t hold the pulses and an end marker.
+    pub fn write_pulses(&mut self, pulses: &[PulseCode]) -> Result<(), AdapterError> {
+        self.create_rmt_data_pulses(pulses)?;
+        self.flush()
+    }
+
+    /// Write colors directly after custom pulses in the same transmission, like a configuration header.
+    ///
+    /// The pulses take up space in the buffer, so fewer LEDs fit behind them than [`Self::capacity`] says.
+    /// Create pulses with durations in nanoseconds with [`Self::pulse`].
+    /// A pulse code with a zero length ends the transmission early.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdapterError::BufferSizeExceeded`] if the buffer can’t hold the pulses and the colors (depending on the [`Overflow`] policy).
+    /// The buffer is then left with an empty frame, so a later flush sends neither the pulses nor the colors.
+    pub fn write_with_preamble<T, I>(
+        &mut self,
+        preamble: &[PulseCode],
+        iterator: T,
+    ) -> Result<(), AdapterError>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<C>,
+    {
+        self.create_rmt_data_with_preamble(preamble, iterator)?;
+        self.flush()
+    }
+
+    /// Transmit existing LED data via the RMT peripheral.
+    pub fn flush(&mut self) -> Result<(), AdapterError> {
+        self.start_transmit()?.finish()
+    }
+
+    /// Encodes colors and starts transmitting them, without waiting for the transmission to complete.
+    ///
+    /// See [`Self::start_transmit`] for how to complete the transmission.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdapterError::BufferSizeExceeded`] if the buffer can’t hold the colors (depending on the [`Overflow`] policy),
+    /// or an error of the transmission.
+    pub fn start_write<T, I>(&mut self, iterator: T) -> Result<Transmission<'_, 'd>, AdapterError>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<C>,
+    {
+        self.create_rmt_data(iterator)?;
+        self.start_transmit()
+    }
+
+    /// Starts transmitting existing LED data, without waiting for the transmission to complete.
+    ///
+    /// The returned [`Transmission`] borrows the driver until it is finished, so the program can do other work in the meantime:
+    ///
+    /// ```rust,ignore
+    /// let mut transmission = led.start_write(frame)?;
+    /// while !transmission.poll() {
+    ///     read_buttons();
+    /// }
+    /// transmission.finish()?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transmission can’t be started.
+    pub fn start_transmit(&mut self) -> Result<Transmission<'_, 'd>, AdapterError> {
+        let channel = self.channel.take().unwrap();
+        match channel.transmit(self.rmt_buffer.as_ref()) {
+            Ok(transaction) => Ok(Transmission {
+                transaction: Some(transaction),
+                channel: &mut self.channel,
+                on_complete: self.on_complete,
+                result: Ok(()),
+            }),
+            Err((e, chan)) => {
+                self.channel = Some(chan);
+                Err(AdapterError::TransmissionError(e))
+            }
+        }
+    }
+}
+
+/// A running transmission of a blocking driver, started by [`GenericRmtSmartLeds::start_transmit`] or [`GenericRmtSmartLeds::start_write`].
+///
+/// Dropping the transmission without calling [`Self::finish`] waits for it to complete, so that the driver can be used again.
+#[must_use = "dropping a transmission waits for it to complete"]
+pub struct Transmission<'a, 'd> {
+    transaction: Option<TxTransaction<'d, 'a>>,
+    /// Where the channel is returned to the driver once the transmission completes.
+    channel: &'a mut Option<Channel<'d, Blocking, Tx>>,
+    on_complete: Option<fn()>,
+    /// The result of the transmission, once it has completed.
+    result: Result<(), AdapterError>,
+}
+
+impl Transmission<'_, '_> {
+    /// Checks whether the transmission has completed.
+    ///
+    /// If the frame is longer than the RMT memory of the channel, this also refills the memory with the next part of the frame.
+    /// In that case, call it often enough that the memory doesn’t run empty, otherwise the LEDs receive a corrupted frame.
+    ///
+    /// The first time the transmission is done, this returns the channel to the driver and calls the
+    /// [completion function](GenericRmtSmartLeds::set_on_complete).
+    pub fn poll(&mut self) -> bool {
+        if let Some(transaction) = &mut self.transaction {
+            if !transaction.poll() {
+                return false;
+            }
+            // Doesn’t block, since the transaction is done.
+            self.complete();
+        }
+        true
+    }
+
+    /// Waits for the transmission to complete.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of the transmission.
+    pub fn finish(mut self) -> Result<(), AdapterError> {
+        self.complete();
+        core::mem::replace(&mut self.result, Ok(()))
+    }
+
+    /// Waits for the transmission, stores its result and returns the channel to the driver.
+    fn complete(&mut self) {
+        let Some(transaction) = self.transaction.take() else {
+            return;
+        };
+        match transaction.wait() {
+            Ok(chan) => {
+                *self.channel = Some(chan);
+                if let Some(on_complete) = self.on_complete {
+                    on_complete();
+                }
+            }
+            Err((e, chan)) => {
+                *self.channel = Some(chan);
+                self.result = Err(AdapterError::TransmissionError(e));
+            }
+        }
+    }
+}
+
+impl Drop for Transmission<'_, '_> {
+    fn drop(&mut self) {
+        // Errors can’t be reported while dropping, but the channel is returned either way.
+        self.complete();
+    }
+}
+
+impl<'d, Buffer, C, Order, Timing> SmartLedsWrite
+    for GenericRmtSmartLeds<'d, Buffer, Blocking, C, Order, Timing>
+where
+    Buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+    C: Color,
+    Order: ColorOrder<C>,
+    Timing: crate::Timing,
+{
+    type Error = AdapterError;
+    type Color = C;
+
+    /// Convert all Color items of the iterator to the RMT format and
+    /// add them to internal buffer, then start a singular RMT operation
+    /// based on that buffer.
+    fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        self.create_rmt_data(iterator)?;
+        self.flush()
+    }
+}
+
+impl<'d, Buffer, C, Order, Timing> GenericRmtSmartLeds<'d, Buffer, Async, C, Order, Timing>
+where
+    Buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+    C: Color,
+    Order: ColorOrder<C>,
+    Timing: crate::Timing,
+{
+    /// Write colors with a higher precision than the LEDs support, like `RGB<u16>` for 8-bit LEDs.
+    ///
+    /// The colors are corrected at their full precision, then rounded to the LEDs’ channel type (with dithering, if enabled).
+    /// `W` must have the same channels as the LED color type, in the same natural order.
+    ///
+    /// Like [`SmartLedsWriteAsync::write`], this prepares the buffer immediately and only transmits once awaited.
+    pub fn write_high_precision<T, I, W>(
+        &mut self,
+        iterator: T,
+    ) -> impl Future<Output = Result<(), AdapterError>>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<W>,
+        W: Color,
+        C: Default,
+    {
+        let res = self.create_rmt_data_high_precision(iterator);
+        self.transmit_async(res)
+    }
+
+    /// Switches off the first `led_count` LEDs right away, and again on [`Self::shutdown`].
+    ///
+    /// Use this right after construction to clear whatever the LEDs latched before a reset.
+    /// Unlike in blocking mode, the LEDs are not switched off when the driver is dropped, since that would require waiting for the transmission.
+    /// Call [`Self::shutdown`] before dropping the driver instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdapterError::BufferSizeExceeded`] if the buffer can’t hold `led_count` LEDs, or an error of the transmission.
+    /// The driver is returned along with the error, with blanking configured unless the LEDs didn’t fit.
+    // The driver is large, but it is moved by value on success as well.
+    #[allow(clippy::result_large_err)]
+    pub async fn with_blanking(mut self, led_count: usize) -> Result<Self, (AdapterError, Self)> {
+        if let Err(error) = self.set_blanking(Some(led_count)) {
+            return Err((error, self));
+        }
+        match self.shutdown().await {
+            Ok(()) => Ok(self),
+            Err(error) => Err((error, self)),
+        }
+    }
+
+    /// Sets the number of LEDs that are switched off on [`Self::shutdown`], or disables blanking with `None`.
+    ///
+    /// Unlike [`Self::with_blanking`], this doesn’t switch the LEDs off right away.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdapterError::BufferSizeExceeded`] if the buffer can’t hold `led_count` LEDs. The previous setting is kept in that case.
+    pub fn set_blanking(&mut self, led_count: Option<usize>) -> Result<(), AdapterError> {
+        if led_count.is_some_and(|count| count > self.capacity()) {
+            return Err(AdapterError::BufferSizeExceeded);
+        }
+        self.blanking = led_count;
+        Ok(())
+    }
+
+    /// Switches off the LEDs configured with [`Self::with_blanking`] or [`Self::set_blanking`], or does nothing if blanking is not configured.
+    ///
+    /// Afterwards, the RMT channel holds the data line at the idle level of the [`Timing`] until the next write.
+    pub async fn shutdown(&mut self) -> Result<(), AdapterError> {
+        if self.blanking.is_none() {
+            return Ok(());
+        }
+        let res = self.create_blank_frame();
+        self.transmit_async(res).await
+    }
+
+    /// Write raw bytes in the order they are sent to the LEDs, like GRB bytes for WS2812.
+    ///
+    /// The bytes are encoded into pulses directly, without any conversion through the color type or [`ColorOrder`].
+    /// Color corrections, power and thermal limiting, soft start, and the overflow and underflow policies are not applied.
+    /// For color types with wider channels, every channel consists of several bytes, most significant byte first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdapterError::InvalidDataLength`] if the bytes don’t make up whole LEDs,
+    /// and [`AdapterError::BufferSizeExceeded`] if the buffer can’t hold them.
+    ///
+    /// Like [`SmartLedsWriteAsync::write`], this prepares the buffer immediately and only transmits once awaited.
+    pub fn write_raw_bytes(
+        &mut self,
+        bytes: &[u8],
+    ) -> impl Future<Output = Result<(), AdapterError>> {
+        let res = self.create_rmt_data_raw(bytes);
+        self.transmit_async(res)
+    }
+
+    /// Transmit custom pulses instead of LED data, like an address programming sequence.
+    ///
+    /// The pulses are sent as they are, followed by nothing but an end marker, so include a reset period if the protocol needs one.
+    /// Create pulses with durations in nanoseconds with [`Self::pulse`].
+    /// A pulse code with a zero length ends the transmission early.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdapterError::BufferSizeExceeded`] if the buffer can’t hold the pulses and an end marker.
+    ///
+    /// Like [`SmartLedsWriteAsync::write`], this prepares the buffer immediately and only transmits once awaited.
+    pub fn write_pulses(
+        &mut self,
+        pulses: &[PulseCode],
+    ) -> impl Future<Output = Result<(), AdapterError>> {
+        let res = self.create_rmt_data_pulses(pulses);
+        self.transmit_async(res)
+    }
+
+    /// Write colors directly after custom pulses in the same transmission, like a configuration header.
+    ///
+    /// The pulses take up space in the buffer, so fewer LEDs fit behind them than [`Self::capacity`] says.
+    /// Create pulses with durations in nanoseconds with [`Self::pulse`].
+    /// A pulse code with a zero length ends the transmission early.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AdapterError::BufferSizeExceeded`] if the buffer can’t hold the pulses and the colors (depending on the [`Overflow`] policy).
+    /// The buffer is then left with an empty frame, so a later flush sends neither the pulses nor the colors.
+    ///
+    /// Like [`SmartLedsWriteAsync::write`], this prepares the buffer immediately and only transmits once awaited.
+    pub fn write_with_preamble<T, I>(
+        &mut self,
+        preamble: &[PulseCode],
+        iterator: T,
+    ) -> impl Future<Output = Result<(), AdapterError>>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<C>,
+    {
+        let res = self.create_rmt_data_with_preamble(preamble, iterator);
+        self.transmit_async(res)
+    }
+
+    /// Transmit the prepared LED data, unless preparing it failed.
+    async fn transmit_async(
+        &mut self,
+        prepared: Result<(), AdapterError>,
+    ) -> Result<(), AdapterError> {
+        prepared?;
+        // Perform the actual RMT operation. We use the u32 values here right away.
+        self.channel
+            .as_mut()
+            .unwrap()
+            .transmit(self.rmt_buffer.as_ref())
+            .await?;
+        if let Some(on_complete) = self.on_complete {
+            on_complete();
+        }
+        Ok(())
+    }
+}
+
+impl<'d, B, C, Order, Timing> GenericRmtSmartLeds<'d, DoubleBuffer<B>, Async, C, Order, Timing>
+where
+    B: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+    C: Color,
+    Order: ColorOrder<C>,
+    Timing: crate::Timing,
+{
+    /// Sends the back buffer, and encodes `next` into the other buffer while the transmission runs.
+    ///
+    /// The steps happen in this order: the buffers switch roles, the transmission of the previously prepared frame starts,
+    /// `next` is encoded, and finally the transmission is awaited. No transmission is running anymore when this returns,
+    /// and `next` is sent by the following swap.
+    /// To send the last prepared frame without preparing another one, pass an empty iterator.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of the transmission, or [`AdapterError::BufferSizeExceeded`] if the buffer can’t hold `next` (depending on the [`Overflow`] policy).
+    /// The transmission is completed either way.
+    pub async fn swap<T, I>(&mut self, next: T) -> Result<(), AdapterError>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<C>,
+    {
+        let sending = self.rmt_buffer.back;
+        self.rmt_buffer.back ^= 1;
+        let [first, second] = &mut self.rmt_buffer.buffers;
+        let (sending, back) = match sending {
+            0 => (first, second),
+            _ => (second, first),
+        };
+
+        let mut transmission = pin!(self.channel.as_mut().unwrap().transmit(sending.as_ref()));
+        let mut sent = None;
+        let on_complete = self.on_complete;
+        let encoder = &mut self.encoder;
+        let mut next = Some(next);
+        let encoded = poll_fn(|cx| {
+            let mut poll_transmission = || {
+                if sent.is_none()
+                    && let Poll::Ready(result) = transmission.as_mut().poll(cx)
+                {
+                    if let (Ok(()), Some(on_complete)) = (&result, on_complete) {
+                        on_complete();
+                    }
+                    sent = Some(result);
+                }
+            };
+            // The first poll starts the transmission.
+            poll_transmission();
+            // Keep polling it after every LED, so that frames longer than the RMT memory are refilled in time.
+            let items = next
+                .take()
+                .unwrap()
+                .into_iter()
+                .inspect(|_| poll_transmission());
+            Poll::Ready(encoder.encode_frame::<C, Order, _>(
+                back.as_mut(),
+                items,
+                |correction, item, index| {
+                    let mut color = item.into();
+                    correction.apply(&mut color, index);
+                    color
+                },
+            ))
+        })
+        .await;
+
+        // Encoding is done, so wait for the rest of the transmission.
+        let sent = match sent {
+            Some(result) => result,
+            None => {
+                let result = transmission.await;
+                if let (Ok(()), Some(on_complete)) = (&result, on_complete) {
+                    on_complete();
+                }
+                result
+            }
+        };
+        sent?;
+        encoded
+    }
+}
+
+impl<'d, Buffer, C, Order, Timing> SmartLedsWriteAsync
+    for GenericRmtSmartLeds<'d, Buffer, Async, C, Order, Timing>
+where
+    Buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+    C: Color,
+    Order: ColorOrder<C>,
+    Timing: crate::Timing,
+{
+    type Error = AdapterError;
+    type Color = C;
+
+    /// Convert all Color items of the iterator to the RMT format and
+    /// add them to internal buffer, then start a singular RMT operation
+    /// based on that buffer.
+    fn write<T, I>(&mut self, iterator: T) -> impl Future<Output = Result<(), Self::Error>>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        // we split the future into a creation part and a sending part
+        // so we can prepare multiple futures and send/await then all at the same time
+        let res = self.create_rmt_data(iterator);
+        self.transmit_async(res)
+    }
+}
+
+impl<'d, Buffer, Mode, C, Order, Timing> Drop
+    for GenericRmtSmartLeds<'d, Buffer, Mode, C, Order, Timing>
+where
+    Buffer: AsRef<[PulseCode]> + AsMut<[PulseCode]>,
+    Mode: DriverMode,
+    C: Color,
+    Order: ColorOrder<C>,
+    Timing: crate::Timing,
+{
+    fn drop(&mut self) {
+        if let Some(blank) = self.blank_on_drop {
+            blank(self);
+        }
     }
+}
 
-    Ok(())
+/// State for encoding frames, which is kept apart from the channel and the buffer,
+/// so that a frame can be encoded while another one is transmitted.
+struct FrameEncoder {
+    /// Pulses for every nibble value, which also determine the pulses for zero and one bits.
+    lut: PulseLut<PulseCode>,
+    reset_pulse: PulseCode,
+    correction: ColorCorrection,
+    power: PowerLimiter,
+    overflow: Overflow,
+    underflow: Underflow,
+    last_write: WriteReport,
+}
+
+impl FrameEncoder {
+    /// Encode a frame into `buffer`, using `correct` to create the corrected LED color for each item.
+    fn encode_frame<C, Order, T>(
+        &mut self,
+        buffer: &mut [PulseCode],
+        iterator: impl IntoIterator<Item = T>,
+        correct: impl Fn(&ColorCorrection, T, usize) -> C,
+    ) -> Result<(), AdapterError>
+    where
+        C: Color,
+        Order: ColorOrder<C>,
+    {
+        self.correction.next_frame();
+
+        let bits = size_of::<C::ChannelType>() * 8;
+        let led_pulses = C::CHANNELS as usize * bits;
+        let capacity = buffer.len().saturating_sub(2) / led_pulses;
+        let mut led_count = 0;
+        let mut dropped = 0;
+        let mut channel_sums = ChannelSums::default();
+
+        // Add all converted iterator items to the buffer.
+        // What happens with items that don’t fit is decided by the overflow policy.
+        let mut items = iterator.into_iter().enumerate();
+        while let Some((index, item)) = items.next() {
+            if index >= capacity {
+                match self.overflow {
+                    Overflow::Error => {
+                        // The buffer already holds part of this frame, which must not be sent without power scaling.
+                        if let [reset, end, ..] = buffer {
+                            *reset = self.reset_pulse;
+                            *end = PulseCode::end_marker();
+                        }
+                        self.last_write = WriteReport::default();
+                        return Err(AdapterError::BufferSizeExceeded);
+                    }
+                    Overflow::Truncate => break,
+                    Overflow::Clamp => {
+                        dropped = 1 + items.count();
+                        break;
+                    }
+                }
+            }
+            let color = correct(&self.correction, item, index);
+            channel_sums.add(&color);
+            // We always start from the beginning of the frame
+            let out = &mut buffer[index * led_pulses..][..led_pulses];
+            convert_colors_to_pulse::<_, Order>(&color, out, &self.lut);
+            led_count += 1;
+        }
+
+        let mut active_microamps = self.power.model.active_microamps::<C>(&channel_sums);
+
+        // Fill the rest of the strip according to the underflow policy.
+        let frame_leds = match self.underflow {
+            Underflow::Leave => led_count,
+            Underflow::Repeat if led_count == 0 => led_count,
+            Underflow::Blank => {
+                buffer[led_count * led_pulses..capacity * led_pulses].fill(self.lut.zero());
+                capacity
+            }
+            Underflow::Repeat => {
+                let pattern = led_count * led_pulses;
+                let mut position = pattern;
+                while position < capacity * led_pulses {
+                    let length = pattern.min(capacity * led_pulses - position);
+                    buffer.copy_within(..length, position);
+                    position += length;
+                }
+                // Estimate the repetitions with the average current of the pattern.
+                active_microamps = active_microamps * capacity as u64 / led_count as u64;
+                capacity
+            }
+        };
+
+        // Finally, add the reset period and an end element.
+        let end = frame_leds * led_pulses;
+        let frame_end = buffer
+            .get_mut(end..end + 2)
+            .ok_or(AdapterError::BufferSizeExceeded)?;
+        frame_end[0] = self.reset_pulse;
+        frame_end[1] = PulseCode::end_marker();
+
+        self.last_write = WriteReport {
+            encoded: led_count,
+            filled: frame_leds - led_count,
+            dropped,
+        };
+
+        // The input can only be iterated once, so a frame over the power budget is scaled down after encoding it.
+        let idle_microamps = frame_leds as u64 * self.power.model.idle_microamps() as u64;
+        let scale = self.power.limit(idle_microamps, active_microamps);
+        if scale < u8::MAX {
+            scale_encoded(&mut buffer[..end], bits, scale, &self.lut);
+        }
+
+        Ok(())
+    }
+}
+
+/// Encodes all channels of a color into `out`, which needs to hold exactly the pulses of one LED.
+fn convert_colors_to_pulse<C, Order>(value: &C, out: &mut [PulseCode], lut: &PulseLut<PulseCode>)
+where
+    C: Color,
+    Order: ColorOrder<C>,
+{
+    let bits = size_of::<C::ChannelType>() * 8;
+    for (channel, out) in out.chunks_exact_mut(bits).enumerate() {
+        lut.encode(Order::get_channel_data(value, channel as u8).into(), out);
+    }
+}
+
+/// Scales all channel values that are encoded in the buffer by `scale / 255`, rounding down.
+fn scale_encoded(buffer: &mut [PulseCode], bits: usize, scale: u8, lut: &PulseLut<PulseCode>) {
+    for channel in buffer.chunks_exact_mut(bits) {
+        let scaled = lut.decode(channel) * scale as usize / u8::MAX as usize;
+        lut.encode(scaled, channel);
+    }
 }
@@ -0,0 +1,104 @@
+//! `const`-compatible replacements for floating-point functions that `core` doesn’t provide.
+//!
+//! These are only used for precomputing tables and configuration values, so they favor simplicity over speed.
+
+const LN_2: f64 = core::f64::consts::LN_2;
+
+/// Natural logarithm. Returns negative infinity for zero and NaN for negative numbers.
+pub(crate) const fn ln(x: f64) -> f64 {
+    if x.is_nan() || x < 0. {
+        return f64::NAN;
+    }
+    if x == 0. {
+        return f64::NEG_INFINITY;
+    }
+    if x.is_infinite() {
+        return f64::INFINITY;
+    }
+    // Split into x = m * 2^e with m in [1, 2).
+    let bits = x.to_bits();
+    let mut exponent = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    let mut mantissa = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000);
+    if exponent == -1023 {
+        // Subnormal number: normalize first.
+        let normalized = ln(x * (1u64 << 54) as f64);
+        return normalized - 54. * LN_2;
+    }
+    // Center the mantissa around 1 for faster convergence.
+    if mantissa > core::f64::consts::SQRT_2 {
+        mantissa /= 2.;
+        exponent += 1;
+    }
+    // ln(m) = 2 * atanh((m - 1) / (m + 1)), where the series argument is at most ~0.17.
+    let y = (mantissa - 1.) / (mantissa + 1.);
+    let y_squared = y * y;
+    let mut term = y;
+    let mut sum = 0.;
+    let mut n = 1;
+    while n < 40 {
+        sum += term / n as f64;
+        term *= y_squared;
+        n += 2;
+    }
+    exponent as f64 * LN_2 + 2. * sum
+}
+
+/// Exponential function e^x.
+pub(crate) const fn exp(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+    if x > 709. {
+        return f64::INFINITY;
+    }
+    if x < -745. {
+        return 0.;
+    }
+    // Split into e^x = 2^k * e^r with |r| <= ln(2) / 2.
+    let k = if x < 0. {
+        (x / LN_2 - 0.5) as i64
+    } else {
+        (x / LN_2 + 0.5) as i64
+    };
+    let r = x - k as f64 * LN_2;
+    let mut term = 1.;
+    let mut sum = 1.;
+    let mut n = 1;
+    while n < 25 {
+        term *= r / n as f64;
+        sum += term;
+        n += 1;
+    }
+    // Apply 2^k in steps that stay within the normal exponent range.
+    let mut k = k;
+    while k > 0 {
+        let step = if k > 1000 { 1000 } else { k };
+        sum *= f64::from_bits(((step + 1023) as u64) << 52);
+        k -= step;
+    }
+    while k < 0 {
+        let step = if k < -1000 { -1000 } else { k };
+        sum *= f64::from_bits(((step + 1023) as u64) << 52);
+        k -= step;
+    }
+    sum
+}
+
+/// `base` raised to the power of `exponent`, for non-negative `base`.
+pub(crate) const fn pow(base: f64, exponent: f64) -> f64 {
+    if base == 0. {
+        return if exponent == 0. { 1. } else { 0. };
+    }
+    exp(exponent * ln(base))
+}
+
+/// Rounds to the nearest integer and clamps to `0..=max`.
+pub(crate) const fn round_clamped(x: f64, max: u32) -> u32 {
+    if x.is_nan() || x <= 0. {
+        0
+    } else if x >= max as f64 {
+        max
+    } else {
+        (x + 0.5) as u32
+    }
+}
@@ -0,0 +1,381 @@
+//! Power limiting based on an estimate of the LEDs’ current draw.
+//!
+//! Long strips at full white can draw more current than the power supply provides.
+//! With a power budget set through [`RmtSmartLeds::set_power_budget`](crate::RmtSmartLeds::set_power_budget),
+//! the driver estimates the current draw of every frame using a [`PowerModel`], and scales the whole frame down uniformly if it exceeds the budget.
+//!
+//! ```rust,ignore
+//! // Leave some headroom on a 2 A supply for the microcontroller.
+//! led.set_power_budget(Some(1800));
+//! led.write(colors)?;
+//! let report = led.power_report();
+//! info!("drawing {} mA, scaled to {}/255", report.limited_milliamps, report.scale);
+//! ```
+//!
+//! The default power model of a driver comes from its [`Timing`](crate::Timing), since LED types differ in their current draw.
+//! These defaults are typical values; measure your strip and adjust the model for precise limiting.
+//!
+//! # Thermal limiting
+//!
+//! The power budget limits the current of every single frame, which protects the power supply.
+//! Enclosed LEDs can additionally overheat from sustained brightness, even if every frame is within the power budget.
+//! A [`ThermalLimit`] set through [`RmtSmartLeds::set_thermal_limit`](crate::RmtSmartLeds::set_thermal_limit)
+//! tracks a moving average of the emitted current over time instead.
+//! Once the average exceeds the thermal budget, the driver fades the brightness down until the average falls below the release threshold,
+//! and then fades it back up. Short flashes are therefore not affected, while sustained high brightness is.
+//!
+//! ```rust,ignore
+//! // Average at most 800 mA over about a minute, fading over five seconds.
+//! led.set_thermal_limit(Some(ThermalLimit::new(800, Duration::from_secs(60))));
+//! ```
+//!
+//! The thermal limiter measures time with [`Instant::now`] whenever a frame is written.
+//! If your application has its own notion of time, supply the timestamp of the next frame with
+//! [`RmtSmartLeds::set_frame_time`](crate::RmtSmartLeds::set_frame_time) before writing it.
+//!
+//! # Soft start
+//!
+//! Switching a long strip from dark to full brightness at once causes a large inrush current, which can reset the microcontroller.
+//! With [`RmtSmartLeds::set_soft_start`](crate::RmtSmartLeds::set_soft_start), the driver ramps the brightness up linearly over the given period,
+//! starting with the first frame that is written. If the LEDs’ power supply is switched on later,
+//! restart the ramp with [`RmtSmartLeds::restart_soft_start`](crate::RmtSmartLeds::restart_soft_start).
+//! Soft start uses the same clock as thermal limiting.
+
+use esp_hal::time::{Duration, Instant};
+
+use crate::{Color, correction::MAX_CHANNELS};
+
+mod budget;
+
+use budget::budget_scale;
+
+/// Estimated current draw of a single LED.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PowerModel {
+    channel_milliamps: [u16; MAX_CHANNELS],
+    idle_microamps: u16,
+}
+
+impl PowerModel {
+    /// Typical 5 V 5050 smart LEDs like the WS2812B draw about 20 mA per channel at full brightness, and about 1 mA when dark.
+    pub const TYPICAL_5V: Self = Self::new(20, 1000);
+    /// Typical 12 V smart LEDs like the WS2815, where the LEDs of each channel are wired in series.
+    pub const TYPICAL_12V: Self = Self::new(5, 1000);
+    /// Typical 24 V smart LEDs like the WS2814.
+    pub const TYPICAL_24V: Self = Self::new(3, 1000);
+
+    /// Creates a power model where every channel draws `channel_milliamps` at full brightness,
+    /// and every LED draws `idle_microamps` even when it is dark.
+    pub const fn new(channel_milliamps: u16, idle_microamps: u16) -> Self {
+        Self {
+            channel_milliamps: [channel_milliamps; MAX_CHANNELS],
+            idle_microamps,
+        }
+    }
+
+    /// Changes the current of a single channel, in the natural order of the color type (see [`Color::channel`]).
+    /// This is useful for RGBW LEDs, where the white LED usually draws more current.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel is larger than four.
+    pub const fn with_channel(mut self, channel: u8, milliamps: u16) -> Self {
+        self.channel_milliamps[channel as usize] = milliamps;
+        self
+    }
+
+    /// Current drawn by a channel at full brightness, in milliamps.
+    pub const fn channel_milliamps(&self, channel: u8) -> u16 {
+        self.channel_milliamps[channel as usize]
+    }
+
+    /// Current drawn by a dark LED, in microamps.
+    pub const fn idle_microamps(&self) -> u16 {
+        self.idle_microamps
+    }
+
+    /// Estimated current draw of LEDs with the given channel sums in addition to their idle current, in microamps.
+    pub(crate) fn active_microamps<C: Color>(&self, sums: &ChannelSums) -> u64 {
+        let max = u64::MAX >> (64 - size_of::<C::ChannelType>() * 8);
+        sums.0
+            .iter()
+            .zip(self.channel_milliamps)
+            .map(|(&sum, milliamps)| sum * milliamps as u64 * 1000 / max)
+            .sum()
+    }
+}
+
+/// Raw channel values of all LEDs in a frame, summed per channel.
+///
+/// The sums are converted into a current only once per frame, which keeps the encoding loop free of divisions.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ChannelSums([u64; MAX_CHANNELS]);
+
+impl ChannelSums {
+    /// Adds the channel values of one LED.
+    pub(crate) fn add<C: Color>(&mut self, color: &C) {
+        let channels = (C::CHANNELS as usize).min(MAX_CHANNELS);
+        for (channel, sum) in self.0[..channels].iter_mut().enumerate() {
+            *sum += color.channel(channel as u8).into() as u64;
+        }
+    }
+}
+
+impl Default for PowerModel {
+    fn default() -> Self {
+        Self::TYPICAL_5V
+    }
+}
+
+/// Thermal budget for the average current draw of the LEDs over time.
+///
+/// See the [module documentation](self#thermal-limiting) for how thermal limiting works.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ThermalLimit {
+    budget_milliamps: u32,
+    window_micros: u64,
+    fade_micros: u64,
+    release_percent: u8,
+}
+
+impl ThermalLimit {
+    /// Creates a thermal limit that keeps the average current below `budget_milliamps`.
+    ///
+    /// `window` is the time constant of the moving average; it should roughly match how fast the enclosure heats up.
+    /// By default, the brightness fades over five seconds, and limiting stops once the average falls below 90 % of the budget.
+    pub const fn new(budget_milliamps: u32, window: Duration) -> Self {
+        Self {
+            budget_milliamps,
+            window_micros: window.as_micros(),
+            fade_micros: 5_000_000,
+            release_percent: 90,
+        }
+    }
+
+    /// Changes how long the brightness takes to fade from full to dark while limiting, and back after limiting stops.
+    pub const fn with_fade(mut self, fade: Duration) -> Self {
+        self.fade_micros = fade.as_micros();
+        self
+    }
+
+    /// Changes the percentage of the budget that the average has to fall below for limiting to stop.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the percentage is larger than 100.
+    pub const fn with_release(mut self, percent: u8) -> Self {
+        assert!(
+            percent <= 100,
+            "release threshold must be within the budget"
+        );
+        self.release_percent = percent;
+        self
+    }
+
+    /// Thermal budget for the average current, in milliamps.
+    pub const fn budget_milliamps(&self) -> u32 {
+        self.budget_milliamps
+    }
+
+    /// Time constant of the moving average.
+    pub const fn window(&self) -> Duration {
+        Duration::from_micros(self.window_micros)
+    }
+
+    /// Time to fade between full brightness and dark.
+    pub const fn fade(&self) -> Duration {
+        Duration::from_micros(self.fade_micros)
+    }
+
+    /// Percentage of the budget below which limiting stops.
+    pub const fn release_percent(&self) -> u8 {
+        self.release_percent
+    }
+}
+
+/// Power estimate and limiting result of the last frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub struct PowerReport {
+    /// Estimated current draw of the frame as written, in milliamps.
+    pub estimated_milliamps: u32,
+    /// Estimated current draw of the frame as sent to the LEDs (after scaling), in milliamps.
+    pub limited_milliamps: u32,
+    /// Scale that was applied to the frame, where 255 means that the frame was not scaled.
+    ///
+    /// This includes the scale of the thermal limiter.
+    pub scale: u8,
+    /// Moving average of the emitted current, in milliamps. This is only tracked while a [`ThermalLimit`] is set.
+    pub average_milliamps: u32,
+    /// Whether the thermal limiter is currently reducing the brightness.
+    pub thermal_limiting: bool,
+    /// Whether the soft start ramp is still in progress.
+    pub soft_starting: bool,
+}
+
+/// Moving average and fade state of the thermal limiter.
+#[derive(Debug, Clone, Copy)]
+struct ThermalState {
+    /// Time of the last frame, in microseconds since the epoch.
+    last_frame: Option<u64>,
+    /// Current of the last frame, which was emitted since then.
+    last_microamps: u64,
+    average_microamps: u64,
+    /// Brightness scale, where [`u16::MAX`] is full brightness.
+    scale: u16,
+    limiting: bool,
+}
+
+impl ThermalState {
+    const fn new() -> Self {
+        Self {
+            last_frame: None,
+            last_microamps: 0,
+            average_microamps: 0,
+            scale: u16::MAX,
+            limiting: false,
+        }
+    }
+
+    /// Advances the moving average and the fade to the time of a new frame, and returns the new brightness scale.
+    fn advance(&mut self, limit: &ThermalLimit, now: u64) -> u16 {
+        let elapsed = match self.last_frame {
+            Some(last) => now.saturating_sub(last),
+            None => 0,
+        };
+        self.last_frame = Some(now);
+
+        // The previous frame was shown during the elapsed time.
+        // This first-order filter stays stable for arbitrarily long gaps between frames.
+        let weight = elapsed as u128;
+        let weighted_sum = self.average_microamps as u128 * limit.window_micros as u128
+            + self.last_microamps as u128 * weight;
+        if let Some(average) = weighted_sum.checked_div(limit.window_micros as u128 + weight) {
+            self.average_microamps = average as u64;
+        }
+
+        let budget = limit.budget_milliamps as u64 * 1000;
+        if self.average_microamps > budget {
+            self.limiting = true;
+        } else if self.average_microamps < budget * limit.release_percent as u64 / 100 {
+            self.limiting = false;
+        }
+
+        let step = match limit.fade_micros {
+            0 => u16::MAX,
+            fade => (elapsed.saturating_mul(u16::MAX as u64) / fade).min(u16::MAX as u64) as u16,
+        };
+        self.scale = if self.limiting {
+            self.scale.saturating_sub(step)
+        } else {
+            self.scale.saturating_add(step)
+        };
+        self.scale
+    }
+}
+
+/// Power limiting state of a driver.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PowerLimiter {
+    pub(crate) model: PowerModel,
+    pub(crate) budget_milliamps: Option<u32>,
+    pub(crate) thermal_limit: Option<ThermalLimit>,
+    /// Timestamp of the next frame, if supplied by the user.
+    pub(crate) frame_time: Option<Instant>,
+    thermal: ThermalState,
+    pub(crate) soft_start: Option<Duration>,
+    /// Start of the soft start ramp, in microseconds since the epoch, or `None` if it starts with the next frame.
+    ramp_start: Option<u64>,
+    pub(crate) report: PowerReport,
+}
+
+impl PowerLimiter {
+    pub(crate) const fn new(model: PowerModel) -> Self {
+        Self {
+            model,
+            budget_milliamps: None,
+            thermal_limit: None,
+            frame_time: None,
+            thermal: ThermalState::new(),
+            soft_start: None,
+            ramp_start: None,
+            report: PowerReport {
+                estimated_milliamps: 0,
+                limited_milliamps: 0,
+                scale: u8::MAX,
+                average_milliamps: 0,
+                thermal_limiting: false,
+                soft_starting: false,
+            },
+        }
+    }
+
+    /// Changes the thermal limit, restarting the moving average.
+    pub(crate) fn set_thermal_limit(&mut self, limit: Option<ThermalLimit>) {
+        self.thermal_limit = limit;
+        self.thermal = ThermalState::new();
+    }
+
+    /// Changes the soft start period and restarts the ramp with the next frame.
+    pub(crate) fn set_soft_start(&mut self, period: Option<Duration>) {
+        self.soft_start = period;
+        self.ramp_start = None;
+    }
+
+    /// Restarts the soft start ramp with the next frame.
+    pub(crate) fn restart_soft_start(&mut self) {
+        self.ramp_start = None;
+    }
+
+    /// Returns the brightness scale of the soft start ramp at the given time, where [`u16::MAX`] is full brightness.
+    fn ramp(&mut self, period: Duration, now: u64) -> u16 {
+        let start = *self.ramp_start.get_or_insert(now);
+        let elapsed = now.saturating_sub(start);
+        match period.as_micros() {
+            0 => u16::MAX,
+            period => {
+                (elapsed.saturating_mul(u16::MAX as u64) / period).min(u16::MAX as u64) as u16
+            }
+        }
+    }
+
+    /// Computes the scale needed to keep a frame within the power and thermal budgets, and records the report.
+    ///
+    /// `idle_microamps` and `active_microamps` are the summed idle and color-dependent currents of all LEDs in the frame.
+    pub(crate) fn limit(&mut self, idle_microamps: u64, active_microamps: u64) -> u8 {
+        let estimated = idle_microamps + active_microamps;
+        let power_scale = budget_scale(self.budget_milliamps, idle_microamps, active_microamps);
+        // Only read the clock if a time-based limiter needs it.
+        let mut frame_time = self.frame_time.take();
+        let mut now = || {
+            frame_time
+                .get_or_insert_with(Instant::now)
+                .duration_since_epoch()
+                .as_micros()
+        };
+        let thermal_scale = match &self.thermal_limit {
+            Some(limit) => self.thermal.advance(limit, now()),
+            None => u16::MAX,
+        };
+        let ramp_scale = match self.soft_start {
+            Some(period) => self.ramp(period, now()),
+            None => u16::MAX,
+        };
+        let scale = (power_scale as u64 * thermal_scale as u64 * ramp_scale as u64
+            / (u16::MAX as u64 * u16::MAX as u64)) as u8;
+        let limited = idle_microamps + active_microamps * scale as u64 / u8::MAX as u64;
+        self.thermal.last_microamps = limited;
+        self.report = PowerReport {
+            estimated_milliamps: estimated.div_ceil(1000) as u32,
+            limited_milliamps: limited.div_ceil(1000) as u32,
+            scale,
+            average_milliamps: self.thermal.average_microamps.div_ceil(1000) as u32,
+            thermal_limiting: self.thermal.limiting,
+            soft_starting: ramp_scale < u16::MAX,
+        };
+        scale
+    }
+}
@@ -0,0 +1,26 @@
+//! Scaling of a frame to the power budget, kept free of hardware dependencies for the host tests.
+
+/// Returns the scale that keeps a frame within `budget_milliamps`, where [`u8::MAX`] leaves the frame unchanged.
+///
+/// `idle_microamps` and `active_microamps` are the summed idle and color-dependent currents of all LEDs in the frame.
+/// Only the color-dependent current can be scaled, so the scale is 0 if the idle current alone exceeds the budget.
+pub(crate) fn budget_scale(
+    budget_milliamps: Option<u32>,
+    idle_microamps: u64,
+    active_microamps: u64,
+) -> u8 {
+    let Some(budget) = budget_milliamps else {
+        return u8::MAX;
+    };
+    let budget = budget as u64 * 1000;
+    if idle_microamps + active_microamps <= budget {
+        return u8::MAX;
+    }
+    if active_microamps == 0 {
+        // Only the idle current exceeds the budget, which no scale can reduce.
+        return 0;
+    }
+    let available = budget.saturating_sub(idle_microamps);
+    // Round down so that the scaled frame stays within the budget.
+    (available * u8::MAX as u64 / active_microamps) as u8
+}
@@ -0,0 +1,185 @@
+//! Driving RGBW LEDs with RGB color data.
+//!
+//! Effects are often authored in RGB, while many LED strips (like the SK6812 RGBW) have a separate white channel.
+//! A [`WhiteExtraction`] strategy derives the white channel from the RGB color,
+//! and [`RgbwAdapter`] wraps any RGBW driver so that it accepts [`RGB8`] colors:
+//!
+//! ```rust,ignore
+//! let driver = Sk68xxRgbwSmartLeds::<{ buffer_size::<RGBW<u8>>(60) }, _>::new(rmt.channel0, peripherals.GPIO2)?;
+//! let mut leds = RgbwAdapter::<_, rgbw::Accurate<4000>>::new(driver);
+//!
+//! leds.write([RGB8::new(255, 200, 150); 60])?;
+//! ```
+
+use core::marker::PhantomData;
+
+use smart_leds_trait::{RGB8, RGBW, SmartLedsWrite, SmartLedsWriteAsync, White};
+
+use crate::color_temperature::blackbody;
+
+/// Strategy to derive the white channel of an RGBW color from an RGB color.
+///
+/// Provided strategies: [`MinSubtraction`], [`Accurate`], [`PureWhiteOnly`]
+// Implementations of this should be vacant enums so they can’t be constructed.
+pub trait WhiteExtraction {
+    /// Convert an RGB color to an RGBW color.
+    fn extract(color: RGB8) -> RGBW<u8>;
+}
+
+/// Moves the common part of all three color channels to the white channel.
+///
+/// This is the simplest strategy and maximizes the use of the white LED, but it assumes that the white LED has the same color as full RGB white,
+/// which is rarely the case. Whites and pastel colors will therefore be tinted by the white LED’s color temperature.
+pub enum MinSubtraction {}
+impl WhiteExtraction for MinSubtraction {
+    fn extract(color: RGB8) -> RGBW<u8> {
+        let white = color.r.min(color.g).min(color.b);
+        RGBW {
+            r: color.r - white,
+            g: color.g - white,
+            b: color.b - white,
+            a: White(white),
+        }
+    }
+}
+
+/// Extracts as much white as possible while preserving the color, given the color temperature of the white LED in Kelvin.
+///
+/// The white LED’s color is approximated as a blackbody radiator at `WHITE_KELVIN`.
+/// Only the part of the color that the white LED can reproduce exactly is moved to the white channel, the remainder stays on the RGB channels.
+/// Common values are 2700 to 3000 K for warm white, 4000 to 4500 K for neutral white and 6000 to 6500 K for cool white LEDs.
+pub enum Accurate<const WHITE_KELVIN: u16> {}
+impl<const WHITE_KELVIN: u16> Accurate<WHITE_KELVIN> {
+    /// Color of the white LED at full brightness.
+    const WHITE_POINT: RGB8 = blackbody(WHITE_KELVIN);
+}
+impl<const WHITE_KELVIN: u16> WhiteExtraction for Accurate<WHITE_KELVIN> {
+    fn extract(color: RGB8) -> RGBW<u8> {
+        let white_point = Self::WHITE_POINT;
+        let channels = [
+            (color.r, white_point.r),
+            (color.g, white_point.g),
+            (color.b, white_point.b),
+        ];
+        // The largest white value for which no channel has to go below zero to compensate.
+        let white = channels
+            .iter()
+            .filter(|(_, white_part)| *white_part > 0)
+            .map(|&(value, white_part)| value as u16 * 255 / white_part as u16)
+            .fold(255, u16::min);
+        let remainder = |value: u8, white_part: u8| value - (white * white_part as u16 / 255) as u8;
+        RGBW {
+            r: remainder(color.r, white_point.r),
+            g: remainder(color.g, white_point.g),
+            b: remainder(color.b, white_point.b),
+            a: White(white as u8),
+        }
+    }
+}
+
+/// Only uses the white channel for pure (unsaturated) whites and grays, and the RGB channels for everything else.
+///
+/// This keeps colors exactly as authored, and is useful if the white LED is much brighter than the RGB LEDs or has a very different tint.
+pub enum PureWhiteOnly {}
+impl WhiteExtraction for PureWhiteOnly {
+    fn extract(color: RGB8) -> RGBW<u8> {
+        if color.r == color.g && color.g == color.b {
+            RGBW {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: White(color.r),
+            }
+        } else {
+            RGBW {
+                r: color.r,
+                g: color.g,
+                b: color.b,
+                a: White(0),
+            }
+        }
+    }
+}
+
+/// Converts all RGB colors of the iterator to RGBW colors with the given [`WhiteExtraction`] strategy.
+pub fn extract_white<Extraction, I>(iterator: I) -> impl Iterator<Item = RGBW<u8>>
+where
+    Extraction: WhiteExtraction,
+    I: IntoIterator,
+    I::Item: Into<RGB8>,
+{
+    iterator
+        .into_iter()
+        .map(|color| Extraction::extract(color.into()))
+}
+
+/// Wraps an RGBW driver (like [`Sk68xxRgbwSmartLeds`](crate::Sk68xxRgbwSmartLeds)) so that it accepts RGB colors.
+///
+/// The white channel is derived with the `Extraction` strategy while writing.
+/// This implements [`SmartLedsWrite`] or [`SmartLedsWriteAsync`], depending on what the wrapped driver implements.
+pub struct RgbwAdapter<Driver, Extraction> {
+    driver: Driver,
+    _extraction: PhantomData<Extraction>,
+}
+
+impl<Driver, Extraction> RgbwAdapter<Driver, Extraction>
+where
+    Extraction: WhiteExtraction,
+{
+    /// Wraps the given RGBW driver.
+    pub const fn new(driver: Driver) -> Self {
+        Self {
+            driver,
+            _extraction: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the wrapped driver.
+    pub const fn driver(&self) -> &Driver {
+        &self.driver
+    }
+
+    /// Returns a mutable reference to the wrapped driver.
+    pub const fn driver_mut(&mut self) -> &mut Driver {
+        &mut self.driver
+    }
+
+    /// Unwraps the driver.
+    pub fn into_inner(self) -> Driver {
+        self.driver
+    }
+}
+
+impl<Driver, Extraction> SmartLedsWrite for RgbwAdapter<Driver, Extraction>
+where
+    Driver: SmartLedsWrite<Color = RGBW<u8>>,
+    Extraction: WhiteExtraction,
+{
+    type Error = Driver::Error;
+    type Color = RGB8;
+
+    fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        self.driver.write(extract_white::<Extraction, _>(iterator))
+    }
+}
+
+impl<Driver, Extraction> SmartLedsWriteAsync for RgbwAdapter<Driver, Extraction>
+where
+    Driver: SmartLedsWriteAsync<Color = RGBW<u8>>,
+    Extraction: WhiteExtraction,
+{
+    type Error = Driver::Error;
+    type Color = RGB8;
+
+    fn write<T, I>(&mut self, iterator: T) -> impl Future<Output = Result<(), Self::Error>>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        self.driver.write(extract_white::<Extraction, _>(iterator))
+    }
+}
@@ -0,0 +1,182 @@
+//! Timings of the supported LED types.
+//!
+//! Every preset is checked against the tolerance window of its datasheet by the host tests in `tests/host`.
+
+use crate::power::PowerModel;
+
+/// Common trait for all different smart LED dependent timings.
+///
+/// All common smart LEDs are controlled by sending PWM-like pulses, in two different configurations for high and low.
+/// The required timings (and tolerances) can be found in the relevant datasheets.
+///
+/// Provided timings: [`Sk68xxTiming`], [`Ws2812bTiming`], [`Ws2812bV5Timing`], [`Ws2812Timing`], [`Ws2811Timing`],
+/// [`Ws2811LowSpeedTiming`], [`Ws2813Timing`], [`Ws2814Timing`], [`Ws2815Timing`], [`Gs8208Timing`], [`Sm16703Timing`],
+/// [`Ucs1903Timing`], [`Apa106Timing`], [`Tm1829Timing`]
+// Implementations of this should be vacant enums so they can’t be constructed.
+pub trait Timing {
+    /// Low time for zero pulse, in nanoseconds.
+    const TIME_0_LOW: u16;
+    /// High time for zero pulse, in nanoseconds.
+    const TIME_0_HIGH: u16;
+    /// Low time for one pulse, in nanoseconds.
+    const TIME_1_LOW: u16;
+    /// High time for one pulse, in nanoseconds.
+    const TIME_1_HIGH: u16;
+    /// Time the line has to stay idle after a frame so that the LEDs latch the data, in nanoseconds.
+    ///
+    /// The driver appends this reset period to every frame it sends.
+    /// The default of 50 µs matches the classic WS2812 family; many newer LEDs require 280 µs or more.
+    const TIME_RESET: u32 = 50_000;
+    /// Whether the LED protocol uses inverted polarity, i.e. the line idles high and all “high” times are sent as low and vice versa.
+    ///
+    /// This is very rare and only used by a few driver ICs like the TM1829.
+    const INVERTED: bool = false;
+    /// Typical current draw of this LED type, used as the default [`PowerModel`] of drivers.
+    ///
+    /// The default is [`PowerModel::TYPICAL_5V`], which fits most 5 V LEDs.
+    const POWER: PowerModel = PowerModel::TYPICAL_5V;
+}
+
+const SK68XX_CODE_PERIOD: u16 = 1200;
+/// Timing for the SK68 collection of LEDs.
+pub enum Sk68xxTiming {}
+impl Timing for Sk68xxTiming {
+    const TIME_0_HIGH: u16 = 320;
+    const TIME_0_LOW: u16 = SK68XX_CODE_PERIOD - Self::TIME_0_HIGH;
+    const TIME_1_HIGH: u16 = 640;
+    const TIME_1_LOW: u16 = SK68XX_CODE_PERIOD - Self::TIME_1_HIGH;
+    const TIME_RESET: u32 = 80_000;
+}
+
+/// Timing for the WS2812B LEDs.
+pub enum Ws2812bTiming {}
+impl Timing for Ws2812bTiming {
+    const TIME_0_HIGH: u16 = 400;
+    const TIME_0_LOW: u16 = 800;
+    const TIME_1_HIGH: u16 = 850;
+    const TIME_1_LOW: u16 = 450;
+}
+
+/// Timing for the WS2812B “V5” LEDs (and WS2812B-Mini), which have tighter high times and a much longer reset than the original WS2812B.
+pub enum Ws2812bV5Timing {}
+impl Timing for Ws2812bV5Timing {
+    const TIME_0_HIGH: u16 = 300;
+    const TIME_0_LOW: u16 = 800;
+    const TIME_1_HIGH: u16 = 800;
+    const TIME_1_LOW: u16 = 320;
+    const TIME_RESET: u32 = 280_000;
+}
+
+/// Timing for the WS2812 LEDs.
+pub enum Ws2812Timing {}
+impl Timing for Ws2812Timing {
+    const TIME_0_HIGH: u16 = 350;
+    const TIME_0_LOW: u16 = 700;
+    const TIME_1_HIGH: u16 = 800;
+    const TIME_1_LOW: u16 = 600;
+}
+
+/// Timing for the WS2811 driver ICs, low-speed mode.
+pub enum Ws2811LowSpeedTiming {}
+impl Timing for Ws2811LowSpeedTiming {
+    const TIME_0_HIGH: u16 = 500;
+    const TIME_0_LOW: u16 = 2000;
+    const TIME_1_HIGH: u16 = 1200;
+    const TIME_1_LOW: u16 = 1300;
+}
+
+/// Timing for the WS2811 driver ICs, high-speed mode.
+pub enum Ws2811Timing {}
+impl Timing for Ws2811Timing {
+    const TIME_0_HIGH: u16 = Ws2811LowSpeedTiming::TIME_0_HIGH / 2;
+    const TIME_0_LOW: u16 = Ws2811LowSpeedTiming::TIME_0_LOW / 2;
+    const TIME_1_HIGH: u16 = Ws2811LowSpeedTiming::TIME_1_HIGH / 2;
+    const TIME_1_LOW: u16 = Ws2811LowSpeedTiming::TIME_1_LOW / 2;
+}
+
+/// Timing for the WS2813 LEDs (with backup data line).
+pub enum Ws2813Timing {}
+impl Timing for Ws2813Timing {
+    const TIME_0_HIGH: u16 = 300;
+    const TIME_0_LOW: u16 = 800;
+    const TIME_1_HIGH: u16 = 800;
+    const TIME_1_LOW: u16 = 320;
+    const TIME_RESET: u32 = 300_000;
+}
+
+/// Timing for the WS2814 RGBW driver ICs, commonly used on 24 V strips.
+pub enum Ws2814Timing {}
+impl Timing for Ws2814Timing {
+    const TIME_0_HIGH: u16 = 300;
+    const TIME_0_LOW: u16 = 800;
+    const TIME_1_HIGH: u16 = 800;
+    const TIME_1_LOW: u16 = 320;
+    const TIME_RESET: u32 = 280_000;
+    const POWER: PowerModel = PowerModel::TYPICAL_24V;
+}
+
+/// Timing for the WS2815 12 V LEDs (with backup data line).
+pub enum Ws2815Timing {}
+impl Timing for Ws2815Timing {
+    const TIME_0_HIGH: u16 = 300;
+    const TIME_0_LOW: u16 = 800;
+    const TIME_1_HIGH: u16 = 800;
+    const TIME_1_LOW: u16 = 320;
+    const TIME_RESET: u32 = 280_000;
+    const POWER: PowerModel = PowerModel::TYPICAL_12V;
+}
+
+/// Timing for the GS8208 12 V LEDs.
+pub enum Gs8208Timing {}
+impl Timing for Gs8208Timing {
+    const TIME_0_HIGH: u16 = 300;
+    const TIME_0_LOW: u16 = 900;
+    const TIME_1_HIGH: u16 = 900;
+    const TIME_1_LOW: u16 = 300;
+    const TIME_RESET: u32 = 280_000;
+    const POWER: PowerModel = PowerModel::TYPICAL_12V;
+}
+
+/// Timing for the SM16703 LEDs and driver ICs.
+pub enum Sm16703Timing {}
+impl Timing for Sm16703Timing {
+    const TIME_0_HIGH: u16 = 300;
+    const TIME_0_LOW: u16 = 900;
+    const TIME_1_HIGH: u16 = 900;
+    const TIME_1_LOW: u16 = 300;
+    const TIME_RESET: u32 = 80_000;
+}
+
+/// Timing for the UCS1903 driver ICs, high-speed (800 kHz) mode.
+pub enum Ucs1903Timing {}
+impl Timing for Ucs1903Timing {
+    const TIME_0_HIGH: u16 = 250;
+    const TIME_0_LOW: u16 = 1000;
+    const TIME_1_HIGH: u16 = 1000;
+    const TIME_1_LOW: u16 = 250;
+    const TIME_RESET: u32 = 50_000;
+}
+
+/// Timing for the APA106 LEDs.
+pub enum Apa106Timing {}
+impl Timing for Apa106Timing {
+    const TIME_0_HIGH: u16 = 350;
+    const TIME_0_LOW: u16 = 1360;
+    const TIME_1_HIGH: u16 = 1360;
+    const TIME_1_LOW: u16 = 350;
+    const TIME_RESET: u32 = 50_000;
+}
+
+/// Timing for the TM1829 driver ICs.
+///
+/// The TM1829 uses inverted polarity: the line idles high and data bits start with a low pulse.
+/// The “high” and “low” times below refer to the logical (non-inverted) signal.
+pub enum Tm1829Timing {}
+impl Timing for Tm1829Timing {
+    const TIME_0_HIGH: u16 = 300;
+    const TIME_0_LOW: u16 = 900;
+    const TIME_1_HIGH: u16 = 800;
+    const TIME_1_LOW: u16 = 400;
+    const TIME_RESET: u32 = 140_000;
+    const INVERTED: bool = true;
+}
@@ -0,0 +1,51 @@
+//! Black frames for blanking, encoded by `PulseLut::encode_blank`.
+
+use crate::encoding::PulseLut;
+
+const ZERO: u32 = 0;
+const ONE: u32 = 1;
+const RESET: u32 = 2;
+const END: u32 = 3;
+/// Pulses of an 8-bit RGB LED.
+const LED_PULSES: usize = 24;
+
+fn lut() -> PulseLut<u32> {
+    PulseLut::new(ZERO, ONE)
+}
+
+#[test]
+fn fits() {
+    let mut buffer = [ONE; 3 * LED_PULSES + 2];
+    assert_eq!(
+        lut().encode_blank(2, LED_PULSES, RESET, END, &mut buffer),
+        Some(2)
+    );
+    assert!(buffer[..2 * LED_PULSES].iter().all(|&pulse| pulse == ZERO));
+    assert_eq!(buffer[2 * LED_PULSES..2 * LED_PULSES + 2], [RESET, END]);
+}
+
+#[test]
+fn clamped_to_shrunk_buffer() {
+    // Blanking was configured for 10 LEDs, but the buffer only holds 3 anymore.
+    let mut buffer = [ONE; 3 * LED_PULSES + 2];
+    assert_eq!(
+        lut().encode_blank(10, LED_PULSES, RESET, END, &mut buffer),
+        Some(3)
+    );
+    assert!(buffer[..3 * LED_PULSES].iter().all(|&pulse| pulse == ZERO));
+    assert_eq!(buffer[3 * LED_PULSES..], [RESET, END]);
+}
+
+#[test]
+fn empty_buffer() {
+    let mut buffer = [ONE; 2];
+    assert_eq!(
+        lut().encode_blank(10, LED_PULSES, RESET, END, &mut buffer),
+        Some(0)
+    );
+    assert_eq!(buffer, [RESET, END]);
+    assert_eq!(
+        lut().encode_blank(10, LED_PULSES, RESET, END, &mut [ONE]),
+        None
+    );
+}
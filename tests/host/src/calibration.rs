@@ -0,0 +1,102 @@
+//! Validation of calibration tables by `Calibration::from_bytes`.
+
+use crate::correction::{Calibration, CalibrationError};
+
+/// Valid table for two RGB LEDs.
+const VALID: &[u8] = b"LEDC\x01\x03\x02\x00\xff\x80\x00\x10\x20\x30";
+
+fn error(data: &'static [u8]) -> Option<CalibrationError> {
+    Calibration::from_bytes(data).err()
+}
+
+#[test]
+fn valid_table() {
+    let calibration = Calibration::from_bytes(VALID).unwrap();
+    assert_eq!(calibration.led_count(), 2);
+    assert_eq!(calibration.channels(), 3);
+    assert_eq!(calibration.gain(0, 1), 0x80);
+    assert_eq!(calibration.gain(1, 2), 0x30);
+    // LEDs past the end of the table are left unchanged.
+    assert_eq!(calibration.gain(2, 0), u8::MAX);
+}
+
+#[test]
+fn valid_at_compile_time() {
+    const CALIBRATION: Calibration = match Calibration::from_bytes(VALID) {
+        Ok(calibration) => calibration,
+        Err(_) => panic!("invalid calibration table"),
+    };
+    assert_eq!(CALIBRATION.led_count(), 2);
+}
+
+#[test]
+fn empty_table() {
+    let calibration = Calibration::from_bytes(b"LEDC\x01\x04\x00\x00").unwrap();
+    assert_eq!(calibration.led_count(), 0);
+    assert_eq!(calibration.gain(0, 3), u8::MAX);
+}
+
+#[test]
+fn short_header() {
+    assert_eq!(error(b""), Some(CalibrationError::InvalidLength));
+    assert_eq!(
+        error(b"LEDC\x01\x03\x02"),
+        Some(CalibrationError::InvalidLength)
+    );
+}
+
+#[test]
+fn invalid_magic() {
+    assert_eq!(
+        error(b"LEDX\x01\x03\x00\x00"),
+        Some(CalibrationError::InvalidMagic)
+    );
+    assert_eq!(
+        error(b"ledc\x01\x03\x00\x00"),
+        Some(CalibrationError::InvalidMagic)
+    );
+}
+
+#[test]
+fn unsupported_version() {
+    assert_eq!(
+        error(b"LEDC\x00\x03\x00\x00"),
+        Some(CalibrationError::UnsupportedVersion(0))
+    );
+    assert_eq!(
+        error(b"LEDC\x02\x03\x00\x00"),
+        Some(CalibrationError::UnsupportedVersion(2))
+    );
+}
+
+#[test]
+fn invalid_channel_count() {
+    assert_eq!(
+        error(b"LEDC\x01\x00\x00\x00"),
+        Some(CalibrationError::InvalidChannelCount(0))
+    );
+    assert_eq!(
+        error(b"LEDC\x01\x06\x00\x00"),
+        Some(CalibrationError::InvalidChannelCount(6))
+    );
+    assert!(Calibration::from_bytes(b"LEDC\x01\x05\x01\x00\x01\x02\x03\x04\x05").is_ok());
+}
+
+#[test]
+fn length_mismatch() {
+    // The header announces two RGB LEDs, but the data has one byte too few or too many.
+    assert_eq!(&VALID[..8], b"LEDC\x01\x03\x02\x00");
+    assert_eq!(
+        error(&VALID[..VALID.len() - 1]),
+        Some(CalibrationError::InvalidLength)
+    );
+    assert_eq!(
+        error(b"LEDC\x01\x03\x02\x00\xff\x80\x00\x10\x20\x30\x40"),
+        Some(CalibrationError::InvalidLength)
+    );
+    // The LED count is little endian, so this announces 256 LEDs.
+    assert_eq!(
+        error(b"LEDC\x01\x03\x00\x01\xff\x80\x00"),
+        Some(CalibrationError::InvalidLength)
+    );
+}
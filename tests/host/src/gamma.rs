@@ -0,0 +1,106 @@
+//! The built-in gamma curves, the interpolation of `GammaLut::lookup` between the table entries,
+//! and brightness control in linear space.
+
+use smart_leds_trait::RGB8;
+
+use crate::correction::{ColorCorrection, GammaLut};
+
+/// Table whose entry `i` is `i * 256`, so that interpolated outputs equal the 8.8 fixed-point table position.
+fn ramp() -> GammaLut {
+    GammaLut::from_table(core::array::from_fn(|index| index as u16 * 256))
+}
+
+#[test]
+fn exact_entries() {
+    let lut = GammaLut::gamma(2.2);
+    let table = GammaLut::from_table(core::array::from_fn(|index| {
+        lut.lookup(index as u64, 255) as u16
+    }));
+    assert!(
+        table == lut,
+        "8-bit inputs must hit the table entries exactly"
+    );
+    assert_eq!(lut.lookup(0, 255), 0);
+    assert_eq!(lut.lookup(255, 255), u16::MAX as u64);
+    assert_eq!(
+        lut.lookup(u16::MAX as u64, u16::MAX as u64),
+        u16::MAX as u64
+    );
+}
+
+#[test]
+fn linear_identity() {
+    for value in 0..=255 {
+        let expected = (value * u16::MAX as u64 + 127) / 255;
+        assert_eq!(GammaLut::LINEAR.lookup(value, 255), expected);
+    }
+}
+
+#[test]
+fn interpolates_rising() {
+    let lut = ramp();
+    for value in (0..=u16::MAX as u64).step_by(97) {
+        assert_eq!(
+            lut.lookup(value, u16::MAX as u64),
+            value * 255 * 256 / u16::MAX as u64
+        );
+    }
+    // Halfway between the entries 10 and 11.
+    assert_eq!(lut.lookup(21, 510), 10 * 256 + 128);
+}
+
+#[test]
+fn interpolates_falling() {
+    let lut = GammaLut::from_table(core::array::from_fn(|index| (255 - index as u16) * 256));
+    for value in (0..=u16::MAX as u64).step_by(97) {
+        let position = value * 255 * 256 / u16::MAX as u64;
+        assert_eq!(lut.lookup(value, u16::MAX as u64), 255 * 256 - position);
+    }
+}
+
+#[test]
+fn monotonic_for_16_bit_inputs() {
+    let lut = GammaLut::CIE1931;
+    let mut previous = 0;
+    for value in 0..=u16::MAX as u64 {
+        let output = lut.lookup(value, u16::MAX as u64);
+        assert!(output >= previous, "output falls at input {value}");
+        previous = output;
+    }
+    assert_eq!(previous, u16::MAX as u64);
+}
+
+#[test]
+fn built_in_curves() {
+    // 0.5^2.2 and the CIE 1931 luminance at half lightness, at 16-bit precision,
+    // up to the error of interpolating between the table entries.
+    for (lut, expected) in [(GammaLut::gamma(2.2), 14264), (GammaLut::CIE1931, 12071)] {
+        let output = lut.lookup(1, 2);
+        assert!(
+            output.abs_diff(expected) <= 2,
+            "{output} instead of {expected}"
+        );
+    }
+    for lut in [GammaLut::gamma(2.2), GammaLut::CIE1931] {
+        assert_eq!(lut.lookup(0, 255), 0);
+        assert_eq!(lut.lookup(255, 255), u16::MAX as u64);
+    }
+}
+
+#[test]
+fn brightness_in_linear_space() {
+    static GAMMA: GammaLut = GammaLut::gamma(2.2);
+    let mut correction = ColorCorrection::new();
+    correction.brightness = 128;
+
+    // Without a transfer function, brightness scales the channel values directly.
+    let mut color = RGB8::new(255, 100, 0);
+    correction.apply(&mut color, 0);
+    assert_eq!(color, RGB8::new(128, 50, 0));
+
+    // With a transfer function, it scales the linear output, so the channel values are gamma-corrected and halved.
+    correction.gamma = Some(&GAMMA);
+    let mut color = RGB8::new(255, 128, 0);
+    correction.apply(&mut color, 0);
+    assert_eq!(color, RGB8::new(128, 28, 0));
+}
@@ -0,0 +1,57 @@
+//! Host tests of the parts of the driver that don’t depend on esp-hal.
+//!
+//! Run with `cargo test` in this directory.
+//! The modules under test are included from the driver source, together with stand-ins for the few driver items they name.
+
+#[path = "../../../src/color.rs"]
+mod color;
+#[path = "../../../src/color_temperature.rs"]
+#[allow(dead_code)]
+mod color_temperature;
+#[path = "../../../src/correction.rs"]
+#[allow(dead_code)]
+mod correction;
+#[path = "../../../src/encoding.rs"]
+#[allow(dead_code)]
+mod encoding;
+#[path = "../../../src/math.rs"]
+#[allow(dead_code)]
+mod math;
+#[path = "../../../src/power/budget.rs"]
+#[allow(dead_code)]
+mod power_budget_scale;
+#[path = "../../../src/rgbw.rs"]
+#[allow(dead_code)]
+mod rgbw;
+#[path = "../../../src/timing.rs"]
+#[allow(dead_code)]
+mod timing;
+
+use color::Color;
+
+/// Stand-in for the power module, since the timings only name its typical power models.
+#[allow(dead_code)]
+mod power {
+    pub struct PowerModel;
+
+    impl PowerModel {
+        pub const TYPICAL_5V: Self = Self;
+        pub const TYPICAL_12V: Self = Self;
+        pub const TYPICAL_24V: Self = Self;
+    }
+}
+
+#[cfg(test)]
+mod blank_frame;
+#[cfg(test)]
+mod calibration;
+#[cfg(test)]
+mod gamma;
+#[cfg(test)]
+mod power_budget;
+#[cfg(test)]
+mod preamble;
+#[cfg(test)]
+mod timing_windows;
+#[cfg(test)]
+mod white_extraction;
@@ -0,0 +1,36 @@
+//! Scaling of frames to the power budget by `budget_scale`.
+
+use crate::power_budget_scale::budget_scale;
+
+#[test]
+fn no_budget() {
+    assert_eq!(budget_scale(None, 1_000_000, 1_000_000), u8::MAX);
+}
+
+#[test]
+fn within_budget() {
+    assert_eq!(budget_scale(Some(1000), 200_000, 800_000), u8::MAX);
+}
+
+#[test]
+fn scaled_into_budget() {
+    let scale = budget_scale(Some(1000), 200_000, 1_600_000);
+    assert_eq!(scale, 127);
+    assert!(200_000 + 1_600_000 * scale as u64 / u8::MAX as u64 <= 1_000_000);
+}
+
+#[test]
+fn zero_current_frame() {
+    assert_eq!(budget_scale(Some(1000), 0, 0), u8::MAX);
+    assert_eq!(budget_scale(Some(0), 0, 0), u8::MAX);
+    assert_eq!(budget_scale(Some(1000), 500_000, 0), u8::MAX);
+}
+
+#[test]
+fn budget_below_idle() {
+    // The frame is dark, but the idle current alone exceeds the budget.
+    assert_eq!(budget_scale(Some(100), 500_000, 0), 0);
+    // Colors can't be shown at all if the idle current uses up the budget.
+    assert_eq!(budget_scale(Some(100), 500_000, 1_000_000), 0);
+    assert_eq!(budget_scale(Some(500), 500_000, 1_000_000), 0);
+}
@@ -0,0 +1,54 @@
+//! Frames after custom pulses, written by `encode_with_preamble`.
+
+use crate::encoding::encode_with_preamble;
+
+const PREAMBLE: u32 = 7;
+const LED: u32 = 1;
+const RESET: u32 = 2;
+const END: u32 = 3;
+
+#[test]
+fn frame_after_preamble() {
+    let mut buffer = [0; 6];
+    let result = encode_with_preamble(&mut buffer, &[PREAMBLE; 2], RESET, END, (), |rest| {
+        assert_eq!(rest.len(), 4);
+        rest.copy_from_slice(&[LED, LED, RESET, END]);
+        Ok(())
+    });
+    assert_eq!(result, Ok(()));
+    assert_eq!(buffer, [PREAMBLE, PREAMBLE, LED, LED, RESET, END]);
+}
+
+#[test]
+fn failed_frame_drops_preamble() {
+    // Like the overflow error of the driver, which ends the frame at the start of the slice it encodes into.
+    let mut buffer = [0; 6];
+    let result = encode_with_preamble(
+        &mut buffer,
+        &[PREAMBLE; 2],
+        RESET,
+        END,
+        "exceeded",
+        |rest| {
+            rest[0] = RESET;
+            rest[1] = END;
+            Err("overflow")
+        },
+    );
+    assert_eq!(result, Err("overflow"));
+    assert_eq!(buffer[..2], [RESET, END]);
+}
+
+#[test]
+fn preamble_too_long() {
+    let mut buffer = [0; 4];
+    let result = encode_with_preamble(&mut buffer, &[PREAMBLE; 3], RESET, END, "exceeded", |_| {
+        unreachable!("no room for a frame")
+    });
+    assert_eq!(result, Err("exceeded"));
+    assert_eq!(buffer, [RESET, END, 0, 0]);
+
+    let mut buffer = [0; 1];
+    let result = encode_with_preamble(&mut buffer, &[], RESET, END, "exceeded", |_| Ok(()));
+    assert_eq!(result, Err("exceeded"));
+}
@@ -0,0 +1,185 @@
+//! Checks every timing preset against the tolerance window of its datasheet.
+
+use crate::timing::*;
+
+/// Inclusive datasheet tolerance windows (in nanoseconds) for a [`Timing`].
+struct ToleranceWindow {
+    time_0_high: (u16, u16),
+    time_0_low: (u16, u16),
+    time_1_high: (u16, u16),
+    time_1_low: (u16, u16),
+    min_reset: u32,
+}
+
+impl ToleranceWindow {
+    #[track_caller]
+    fn check<T: Timing>(&self) {
+        fn within(name: &str, value: u16, (min, max): (u16, u16)) {
+            assert!(
+                (min..=max).contains(&value),
+                "{name} of {value} ns is outside of {min}..={max} ns"
+            );
+        }
+        within("T0H", T::TIME_0_HIGH, self.time_0_high);
+        within("T0L", T::TIME_0_LOW, self.time_0_low);
+        within("T1H", T::TIME_1_HIGH, self.time_1_high);
+        within("T1L", T::TIME_1_LOW, self.time_1_low);
+        assert!(
+            T::TIME_RESET >= self.min_reset,
+            "reset of {} ns is shorter than {} ns",
+            T::TIME_RESET,
+            self.min_reset
+        );
+    }
+}
+
+/// Windows shared by the WS2812B V5, WS2813, WS2814 and WS2815 datasheets.
+const WS281X_V5_WINDOW: ToleranceWindow = ToleranceWindow {
+    time_0_high: (220, 380),
+    time_0_low: (580, 1600),
+    time_1_high: (580, 1600),
+    time_1_low: (220, 420),
+    min_reset: 280_000,
+};
+
+#[test]
+fn ws2812b_v5() {
+    WS281X_V5_WINDOW.check::<Ws2812bV5Timing>();
+}
+
+#[test]
+fn ws2813() {
+    WS281X_V5_WINDOW.check::<Ws2813Timing>();
+}
+
+#[test]
+fn ws2814() {
+    WS281X_V5_WINDOW.check::<Ws2814Timing>();
+}
+
+#[test]
+fn ws2815() {
+    WS281X_V5_WINDOW.check::<Ws2815Timing>();
+}
+
+#[test]
+fn gs8208() {
+    ToleranceWindow {
+        time_0_high: (200, 400),
+        time_0_low: (650, 1000),
+        time_1_high: (650, 1000),
+        time_1_low: (200, 400),
+        min_reset: 280_000,
+    }
+    .check::<Gs8208Timing>();
+}
+
+#[test]
+fn sm16703() {
+    ToleranceWindow {
+        time_0_high: (200, 400),
+        time_0_low: (800, 1000),
+        time_1_high: (800, 1000),
+        time_1_low: (200, 400),
+        min_reset: 80_000,
+    }
+    .check::<Sm16703Timing>();
+}
+
+#[test]
+fn ucs1903() {
+    ToleranceWindow {
+        time_0_high: (100, 400),
+        time_0_low: (850, 1150),
+        time_1_high: (850, 1150),
+        time_1_low: (100, 400),
+        min_reset: 24_000,
+    }
+    .check::<Ucs1903Timing>();
+}
+
+#[test]
+fn apa106() {
+    ToleranceWindow {
+        time_0_high: (200, 500),
+        time_0_low: (1210, 1510),
+        time_1_high: (1210, 1510),
+        time_1_low: (200, 500),
+        min_reset: 50_000,
+    }
+    .check::<Apa106Timing>();
+}
+
+#[test]
+fn tm1829() {
+    ToleranceWindow {
+        time_0_high: (200, 400),
+        time_0_low: (800, 1000),
+        time_1_high: (600, 1000),
+        time_1_low: (300, 600),
+        min_reset: 140_000,
+    }
+    .check::<Tm1829Timing>();
+    const { assert!(Tm1829Timing::INVERTED) };
+}
+
+#[test]
+fn ws2812() {
+    ToleranceWindow {
+        time_0_high: (200, 500),
+        time_0_low: (650, 950),
+        time_1_high: (550, 850),
+        time_1_low: (450, 750),
+        min_reset: 50_000,
+    }
+    .check::<Ws2812Timing>();
+}
+
+#[test]
+fn ws2812b() {
+    ToleranceWindow {
+        time_0_high: (250, 550),
+        time_0_low: (700, 1000),
+        time_1_high: (650, 950),
+        time_1_low: (300, 600),
+        min_reset: 50_000,
+    }
+    .check::<Ws2812bTiming>();
+}
+
+#[test]
+fn sk68xx() {
+    ToleranceWindow {
+        time_0_high: (150, 450),
+        time_0_low: (750, 1050),
+        time_1_high: (450, 750),
+        time_1_low: (450, 750),
+        min_reset: 80_000,
+    }
+    .check::<Sk68xxTiming>();
+}
+
+#[test]
+fn ws2811_low_speed() {
+    ToleranceWindow {
+        time_0_high: (350, 650),
+        time_0_low: (1850, 2150),
+        time_1_high: (1050, 1350),
+        time_1_low: (1150, 1450),
+        min_reset: 50_000,
+    }
+    .check::<Ws2811LowSpeedTiming>();
+}
+
+#[test]
+fn ws2811() {
+    // High-speed mode halves the times and tolerances of low-speed mode.
+    ToleranceWindow {
+        time_0_high: (175, 325),
+        time_0_low: (925, 1075),
+        time_1_high: (525, 675),
+        time_1_low: (575, 725),
+        min_reset: 50_000,
+    }
+    .check::<Ws2811Timing>();
+}
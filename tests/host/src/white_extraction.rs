@@ -0,0 +1,151 @@
+//! White extraction strategies of the `rgbw` module.
+
+use smart_leds_trait::{RGB8, RGBW, White};
+
+use crate::{
+    color_temperature::blackbody,
+    rgbw::{Accurate, MinSubtraction, PureWhiteOnly, WhiteExtraction, extract_white},
+};
+
+type Neutral = Accurate<4000>;
+
+/// Adds the light of the white LED back to the RGB channels.
+fn reconstruct(color: RGBW<u8>, white_point: RGB8) -> RGB8 {
+    let white = |part: u8| (color.a.0 as u16 * part as u16 / 255) as u8;
+    RGB8::new(
+        color.r + white(white_point.r),
+        color.g + white(white_point.g),
+        color.b + white(white_point.b),
+    )
+}
+
+#[test]
+fn black() {
+    assert_eq!(
+        Neutral::extract(RGB8::default()),
+        RGBW {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: White(0)
+        }
+    );
+}
+
+#[test]
+fn white_point_uses_only_white() {
+    let white_point = blackbody(4000);
+    assert_eq!(
+        Neutral::extract(white_point),
+        RGBW {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: White(255)
+        }
+    );
+}
+
+#[test]
+fn primaries_use_no_white() {
+    for color in [
+        RGB8::new(255, 0, 0),
+        RGB8::new(0, 255, 0),
+        RGB8::new(0, 0, 255),
+    ] {
+        let extracted = Neutral::extract(color);
+        assert_eq!(extracted.a.0, 0);
+        assert_eq!(RGB8::new(extracted.r, extracted.g, extracted.b), color);
+    }
+}
+
+#[test]
+fn preserves_colors() {
+    for kelvin in [2700, 4000, 6500] {
+        let white_point = blackbody(kelvin);
+        for r in (0..=255).step_by(15) {
+            for g in (0..=255).step_by(15) {
+                for b in (0..=255).step_by(15) {
+                    let color = RGB8::new(r, g, b);
+                    let extracted = match kelvin {
+                        2700 => Accurate::<2700>::extract(color),
+                        4000 => Accurate::<4000>::extract(color),
+                        _ => Accurate::<6500>::extract(color),
+                    };
+                    assert_eq!(reconstruct(extracted, white_point), color, "at {kelvin} K");
+                    // At least one channel is used up by the white LED (up to rounding), unless it is already at full brightness.
+                    if extracted.a.0 < 255 {
+                        let used_up = [
+                            (extracted.r, white_point.r),
+                            (extracted.g, white_point.g),
+                            (extracted.b, white_point.b),
+                        ]
+                        .iter()
+                        .any(|&(rest, part)| part > 0 && rest <= 2);
+                        assert!(
+                            used_up,
+                            "{color:?} at {kelvin} K leaves white unused: {extracted:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn min_subtraction() {
+    assert_eq!(
+        MinSubtraction::extract(RGB8::new(200, 150, 100)),
+        RGBW {
+            r: 100,
+            g: 50,
+            b: 0,
+            a: White(100)
+        }
+    );
+    assert_eq!(
+        MinSubtraction::extract(RGB8::new(255, 255, 255)),
+        RGBW {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: White(255)
+        }
+    );
+}
+
+#[test]
+fn pure_white_only() {
+    assert_eq!(
+        PureWhiteOnly::extract(RGB8::new(80, 80, 80)),
+        RGBW {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: White(80)
+        }
+    );
+    assert_eq!(
+        PureWhiteOnly::extract(RGB8::new(80, 80, 81)),
+        RGBW {
+            r: 80,
+            g: 80,
+            b: 81,
+            a: White(0)
+        }
+    );
+}
+
+#[test]
+fn extract_white_iterator() {
+    let colors = [RGB8::new(10, 20, 30), RGB8::new(0, 0, 0)];
+    let extracted: Vec<_> = extract_white::<MinSubtraction, _>(colors).collect();
+    assert_eq!(
+        extracted,
+        [
+            MinSubtraction::extract(colors[0]),
+            MinSubtraction::extract(colors[1])
+        ]
+    );
+}